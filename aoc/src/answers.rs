@@ -0,0 +1,94 @@
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// Known answers keyed by (day, part), read from a small
+// subset of TOML:
+//
+//   [day07]
+//   part1 = "vtzay"
+//   part2 = 910
+pub struct Answers {
+    known: HashMap<(usize, u32), String>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Check {
+    Pass,
+    Fail(String),
+    Unknown,
+}
+
+fn parse_day(header: &str) -> Option<usize> {
+    header.strip_prefix("day").and_then(|n| n.parse().ok())
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        Some(value[1..(value.len() - 1)].to_string())
+    } else if !value.is_empty() && !value.contains(char::is_whitespace) {
+        Some(value.to_string())
+    } else {
+        None
+    }
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Answers, String> {
+        let mut known = HashMap::new();
+        let mut day = None;
+        for (num, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let bad = |what: &str| format!("line {}: {}: {}", num + 1, what, line);
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                day = Some(parse_day(&line[1..(line.len() - 1)]).ok_or_else(|| bad("expected [dayNN]"))?);
+                continue;
+            }
+            let d = day.ok_or_else(|| bad("answer outside of a [dayNN] table"))?;
+            let (key, value) = match line.find('=') {
+                Some(i) => (line[..i].trim(), line[(i + 1)..].trim()),
+                None => return Err(bad("expected partN = answer")),
+            };
+            let part = match key {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(bad("expected part1 or part2")),
+            };
+            let value = parse_value(value).ok_or_else(|| bad("bad answer"))?;
+            known.insert((d, part), value);
+        }
+
+        Ok(Answers { known })
+    }
+
+    pub fn load(path: &PathBuf) -> Result<Answers, String> {
+        let text = proj_self::try_file_to_str(path).map_err(|e| e.to_string())?;
+
+        Answers::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn check(&self, day: usize, part: u32, answer: &str) -> Check {
+        match self.known.get(&(day, part)) {
+            Some(expected) if expected == answer => Check::Pass,
+            Some(expected) => Check::Fail(expected.clone()),
+            None => Check::Unknown,
+        }
+    }
+}
+
+#[test]
+fn test() {
+    let answers = Answers::parse("# comment\n[day07]\npart1 = \"vtzay\"\npart2 = 910\n\n[day08]\npart2=\"5035\"\n").unwrap();
+    assert_eq!(Check::Pass, answers.check(7, 1, "vtzay"));
+    assert_eq!(Check::Pass, answers.check(7, 2, "910"));
+    assert_eq!(Check::Fail("910".to_string()), answers.check(7, 2, "911"));
+    assert_eq!(Check::Unknown, answers.check(8, 1, "3880"));
+    assert_eq!(Check::Pass, answers.check(8, 2, "5035"));
+    assert!(Answers::parse("part1 = 1").is_err());
+    assert!(Answers::parse("[day01]\npart3 = 1").is_err());
+    assert!(Answers::parse("[dayone]").is_err());
+    assert!(Answers::parse("[day01]\npart1 = two words").is_err());
+}
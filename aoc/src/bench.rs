@@ -0,0 +1,99 @@
+
+use std::time::Duration;
+use solution::Timed;
+use json;
+
+// min and median of one step over every run
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+}
+
+impl Stats {
+    pub fn of(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+
+        Stats { min: sorted[0], median }
+    }
+
+    fn json(&self) -> String {
+        json::object(&[("min_ns", self.min.as_nanos().to_string()), ("median_ns", self.median.as_nanos().to_string())])
+    }
+}
+
+// timing for one day over a number of runs
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Bench {
+    pub day: usize,
+    pub runs: usize,
+    pub parse: Stats,
+    pub parts: Vec<(u32, Stats)>,
+}
+
+impl Bench {
+    // runs is never empty
+    pub fn of(day: usize, runs: &[Timed]) -> Bench {
+        let parse: Vec<Duration> = runs.iter().map(|r| r.parse).collect();
+        let parts = runs[0].parts.iter().enumerate().map(|(i, &(p, _, _))| {
+            let samples: Vec<Duration> = runs.iter().map(|r| r.parts[i].2).collect();
+            (p, Stats::of(&samples))
+        }).collect();
+
+        Bench { day, runs: runs.len(), parse: Stats::of(&parse), parts }
+    }
+
+    pub fn text(&self) -> Vec<String> {
+        let line = |step: String, s: &Stats| {
+            format!("Day {:02} {:<7} min {:>12} median {:>12}", self.day, step, millis(s.min), millis(s.median))
+        };
+        let mut lines = vec![line("parse".to_string(), &self.parse)];
+        for &(p, ref s) in &self.parts {
+            lines.push(line(format!("part {}", p), s));
+        }
+
+        lines
+    }
+
+    pub fn json(&self) -> String {
+        let names: Vec<String> = self.parts.iter().map(|&(p, _)| format!("part{}", p)).collect();
+        let mut fields = vec![
+            ("day", self.day.to_string()),
+            ("runs", self.runs.to_string()),
+            ("parse", self.parse.json()),
+        ];
+        for (name, (_, s)) in names.iter().zip(self.parts.iter()) {
+            fields.push((name.as_str(), s.json()));
+        }
+
+        json::object(&fields)
+    }
+}
+
+fn millis(d: Duration) -> String {
+    format!("{:.3}ms", d.as_secs_f64() * 1000.0)
+}
+
+#[test]
+fn test() {
+    let ms = Duration::from_millis;
+    assert_eq!(Stats { min: ms(1), median: ms(3) }, Stats::of(&[ms(5), ms(1), ms(3)]));
+    assert_eq!(Stats { min: ms(2), median: ms(5) }, Stats::of(&[ms(8), ms(2), ms(4), ms(6)]));
+
+    let run = |parse: u64, p1: u64| Timed { parse: ms(parse), parts: vec![(1, "x".to_string(), ms(p1))] };
+    let b = Bench::of(15, &[run(1, 30), run(2, 10), run(3, 20)]);
+    assert_eq!(Stats { min: ms(1), median: ms(2) }, b.parse);
+    assert_eq!(vec![(1, Stats { min: ms(10), median: ms(20) })], b.parts);
+    assert_eq!(
+        "{\"day\":15,\"runs\":3,\"parse\":{\"min_ns\":1000000,\"median_ns\":2000000},\"part1\":{\"min_ns\":10000000,\"median_ns\":20000000}}",
+        b.json()
+    );
+    assert_eq!("Day 15 part 1  min     10.000ms median     20.000ms", b.text()[1]);
+}
@@ -0,0 +1,34 @@
+
+// a JSON string literal, quotes included
+pub fn string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
+
+// an object from already encoded values, keeping field order
+pub fn object(fields: &[(&str, String)]) -> String {
+    let fields: Vec<String> = fields.iter().map(|&(k, ref v)| format!("{}:{}", string(k), v)).collect();
+
+    format!("{{{}}}", fields.join(","))
+}
+
+#[test]
+fn test() {
+    assert_eq!("\"vtzay\"", string("vtzay"));
+    assert_eq!("\"a\\\"b\\\\c\\nd\\u0001\"", string("a\"b\\c\nd\u{1}"));
+    assert_eq!("{\"day\":7,\"answer\":\"910\"}", object(&[("day", "7".to_string()), ("answer", string("910"))]));
+    assert_eq!("{}", object(&[]));
+}
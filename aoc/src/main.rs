@@ -0,0 +1,453 @@
+
+extern crate proj_self;
+extern crate solution;
+extern crate day00;
+extern crate day01;
+extern crate day02;
+extern crate day03;
+extern crate day04;
+extern crate day05;
+extern crate day06;
+extern crate day07;
+extern crate day08;
+extern crate day09;
+extern crate day10;
+extern crate day11;
+extern crate day12;
+extern crate day13;
+extern crate day14;
+extern crate day15;
+extern crate day16;
+extern crate day17;
+extern crate day18;
+extern crate day19;
+extern crate day20;
+extern crate day21;
+extern crate day22;
+extern crate day23;
+extern crate day24;
+extern crate day25;
+
+mod answers;
+mod bench;
+mod json;
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use answers::Answers;
+use answers::Check;
+use bench::Bench;
+use proj_self::Resolver;
+use proj_self::fetch::Fetcher;
+use solution::Day;
+
+#[derive(Debug, Eq, PartialEq)]
+enum Days {
+    All,
+    One(usize),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Mode {
+    Run,
+    Fetch,
+    Verify,
+    Bench,
+    Dot,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Command {
+    mode: Mode,
+    days: Days,
+    part: Option<u32>,
+    input: Option<PathBuf>,
+    answers: Option<PathBuf>,
+    runs: usize,
+    json: bool,
+}
+
+impl Command {
+    fn new(mode: Mode, days: Days) -> Command {
+        Command { mode, days, part: None, input: None, answers: None, runs: 1, json: false }
+    }
+}
+
+// every day's solutions, indexed by day number
+fn days() -> Vec<Day> {
+    vec![
+        Day::of::<day00::Day00>(),
+        Day::of::<day01::Day01>(),
+        Day::of::<day02::Day02>(),
+        Day::of::<day03::Day03>(),
+        Day::of::<day04::Day04>(),
+        Day::of::<day05::Day05>(),
+        Day::of::<day06::Day06>(),
+        Day::of::<day07::Day07>(),
+        Day::of::<day08::Day08>(),
+        Day::of::<day09::Day09>(),
+        Day::of::<day10::Day10>(),
+        Day::of::<day11::Day11>(),
+        Day::of::<day12::Day12>(),
+        Day::of::<day13::Day13>(),
+        Day::of::<day14::Day14>(),
+        Day::of::<day15::Day15>(),
+        Day::of::<day16::Day16>(),
+        Day::of::<day17::Day17>(),
+        Day::of::<day18::Day18>(),
+        Day::of::<day19::Day19>(),
+        Day::of::<day20::Day20>(),
+        Day::of::<day21::Day21>(),
+        Day::of::<day22::Day22>(),
+        Day::of::<day23::Day23>(),
+        Day::of::<day24::Day24>(),
+        Day::of::<day25::Day25>(),
+    ]
+}
+
+fn usage() -> String {
+    "Usage: aoc run <day|all> [--part <1|2>] [--input <dir|file|->] [--json]\n       aoc verify <day|all> [--part <1|2>] [--input <dir|file|->] [--answers <file>]\n       aoc bench <day|all> [--part <1|2>] [--input <dir|file|->] [--runs <n>] [--json]\n       aoc fetch <day|all>\n       aoc dot <8|18|23> [--input <dir|file|->]".to_string()
+}
+
+fn parse_day(s: &str, count: usize) -> Result<Days, String> {
+    if s == "all" {
+        return Ok(Days::All);
+    }
+    match s.parse() {
+        Ok(d) if d < count => Ok(Days::One(d)),
+        _ => Err(format!("Unknown day: {}", s)),
+    }
+}
+
+fn parse_part(s: Option<&String>) -> Result<u32, String> {
+    match s.map(|x| x.parse()) {
+        Some(Ok(p)) if p == 1 || p == 2 => Ok(p),
+        _ => Err("--part must be 1 or 2".to_string()),
+    }
+}
+
+fn parse_args(args: &[String], count: usize) -> Result<Command, String> {
+    if args.len() < 2 {
+        return Err(usage());
+    }
+    let mode = match args[0].as_str() {
+        "run" => Mode::Run,
+        "fetch" => Mode::Fetch,
+        "verify" => Mode::Verify,
+        "bench" => Mode::Bench,
+        "dot" => Mode::Dot,
+        _ => return Err(usage()),
+    };
+    let mut cmd = Command::new(mode, parse_day(&args[1], count)?);
+    let mut i = 2;
+    while i < args.len() {
+        if cmd.mode == Mode::Fetch {
+            return Err(format!("Unexpected argument: {}\n{}", args[i], usage()));
+        }
+        match args[i].as_str() {
+            "--part" | "-p" if cmd.mode != Mode::Dot => {
+                i += 1;
+                cmd.part = Some(parse_part(args.get(i))?);
+            },
+            "--input" | "-i" => {
+                i += 1;
+                match args.get(i) {
+                    Some(dir) => cmd.input = Some(PathBuf::from(dir)),
+                    None => return Err("--input needs a directory, a file or -".to_string()),
+                }
+            },
+            "--answers" if cmd.mode == Mode::Verify => {
+                i += 1;
+                match args.get(i) {
+                    Some(file) => cmd.answers = Some(PathBuf::from(file)),
+                    None => return Err("--answers needs a file".to_string()),
+                }
+            },
+            "--runs" | "-n" if cmd.mode == Mode::Bench => {
+                i += 1;
+                match args.get(i).map(|x| x.parse()) {
+                    Some(Ok(n)) if n > 0 => cmd.runs = n,
+                    _ => return Err("--runs must be a positive number".to_string()),
+                }
+            },
+            "--json" if cmd.mode == Mode::Bench || cmd.mode == Mode::Run => cmd.json = true,
+            x => return Err(format!("Unexpected argument: {}\n{}", x, usage())),
+        }
+        i += 1;
+    }
+
+    match (&cmd.mode, &cmd.days) {
+        (&Mode::Dot, &Days::One(8)) | (&Mode::Dot, &Days::One(18)) | (&Mode::Dot, &Days::One(23)) => (),
+        (&Mode::Dot, _) => return Err("dot needs day 8, 18 or 23".to_string()),
+        _ => (),
+    }
+    if cmd.days == Days::All && cmd.input.as_deref().is_some_and(is_single_input) {
+        return Err("--input <file|-> needs a single day".to_string());
+    }
+
+    Ok(cmd)
+}
+
+// stdin or a file, as opposed to a directory of inputs
+fn is_single_input(path: &Path) -> bool {
+    path.as_os_str() == "-" || path.is_file()
+}
+
+// the input named on the command line, or the day's own input file
+fn load(cmd: &Command, inputs: &Resolver, day: &str, file: &str) -> Result<String, proj_self::Error> {
+    match cmd.input {
+        Some(ref path) if is_single_input(path) => proj_self::try_read_input(path),
+        _ => proj_self::try_file_to_str(&inputs.find(day, file)?),
+    }
+}
+
+// returns false if any day could not be run
+fn run(cmd: &Command, days: &[Day], inputs: &Resolver) -> bool {
+    let mut ok = true;
+    for d in selected(cmd, days) {
+        match solve_day(cmd, &days[d], inputs, d) {
+            Ok(answers) => {
+                for (p, a, t) in answers {
+                    if cmd.json {
+                        println!("{}", json::object(&[
+                            ("day", d.to_string()),
+                            ("part", p.to_string()),
+                            ("answer", json::string(&a)),
+                            ("duration_ns", t.as_nanos().to_string()),
+                        ]));
+                    } else {
+                        println!("Day {:02} part {}: {}", d, p, a);
+                    }
+                }
+            },
+            Err(e) => {
+                if cmd.json {
+                    println!("{}", json::object(&[("day", d.to_string()), ("error", json::string(&e.to_string()))]));
+                }
+                eprintln!("Day {:02}: {}", d, e);
+                ok = false;
+            },
+        }
+    }
+
+    ok
+}
+
+// returns false on any wrong answer or unrunnable day
+fn verify(cmd: &Command, days: &[Day], inputs: &Resolver, known: &Answers) -> bool {
+    let (mut pass, mut fail, mut unknown) = (0, 0, 0);
+    for d in selected(cmd, days) {
+        match solve_day(cmd, &days[d], inputs, d) {
+            Ok(answers) => {
+                for (p, a, _t) in answers {
+                    match known.check(d, p, &a) {
+                        Check::Pass => {
+                            pass += 1;
+                            println!("Day {:02} part {}: pass", d, p);
+                        },
+                        Check::Fail(expected) => {
+                            fail += 1;
+                            println!("Day {:02} part {}: FAIL (got {}, expected {})", d, p, a, expected);
+                        },
+                        Check::Unknown => {
+                            unknown += 1;
+                            println!("Day {:02} part {}: unknown (got {})", d, p, a);
+                        },
+                    }
+                }
+            },
+            Err(e) => {
+                fail += 1;
+                println!("Day {:02}: FAIL ({})", d, e);
+            },
+        }
+    }
+    println!("{} passed, {} failed, {} unknown", pass, fail, unknown);
+
+    fail == 0
+}
+
+// times every selected day cmd.runs times over, reusing the
+// loaded input so that only the solution is measured
+fn bench(cmd: &Command, days: &[Day], inputs: &Resolver) -> bool {
+    let mut ok = true;
+    let mut results = vec![];
+    for d in selected(cmd, days) {
+        let day = &days[d];
+        let parts = match cmd.part {
+            Some(p) => vec![p],
+            None => vec![1, 2],
+        };
+        let input = match load(cmd, inputs, &format!("day{:02}", d), day.input) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Day {:02}: {}", d, e);
+                ok = false;
+                continue;
+            },
+        };
+        let runs: Vec<_> = (0..cmd.runs).map(|_| (day.timed)(&input, &parts)).collect();
+        let result = Bench::of(d, &runs);
+        if !cmd.json {
+            for line in result.text() {
+                println!("{}", line);
+            }
+        }
+        results.push(result);
+    }
+    if cmd.json {
+        let json: Vec<String> = results.iter().map(|r| r.json()).collect();
+        println!("[\n{}\n]", json.join(",\n"));
+    }
+
+    ok
+}
+
+// the day numbers a command covers
+fn selected(cmd: &Command, days: &[Day]) -> Vec<usize> {
+    match cmd.days {
+        Days::All => (0..days.len()).collect(),
+        Days::One(d) => vec![d],
+    }
+}
+
+// (part, answer, time taken) for each requested part of day d
+fn solve_day(cmd: &Command, day: &Day, inputs: &Resolver, d: usize) -> Result<Vec<(u32, String, Duration)>, proj_self::Error> {
+    let parts = match cmd.part {
+        Some(p) => vec![p],
+        None => vec![1, 2],
+    };
+    let input = load(cmd, inputs, &format!("day{:02}", d), day.input)?;
+
+    Ok((day.timed)(&input, &parts).parts)
+}
+
+// answers.toml at the root of the workspace the binary was built from
+fn default_answers() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..").join("answers.toml")
+}
+
+// Graphviz for the control flow of the days whose inputs
+// are programs
+fn dot(cmd: &Command, days: &[Day], inputs: &Resolver) -> bool {
+    let mut ok = true;
+    for d in selected(cmd, days) {
+        let to_dot: fn(&str) -> String = match d {
+            8 => day08::dot,
+            18 => day18::dot,
+            23 => day23::dot,
+            _ => {
+                eprintln!("Day {:02}: the input isn't a program; try 8, 18 or 23", d);
+                ok = false;
+                continue;
+            },
+        };
+        match load(cmd, inputs, &format!("day{:02}", d), days[d].input) {
+            Ok(input) => print!("{}", to_dot(&input)),
+            Err(e) => {
+                eprintln!("Day {:02}: {}", d, e);
+                ok = false;
+            },
+        }
+    }
+
+    ok
+}
+
+// downloads whatever isn't cached yet; day 0 isn't a real puzzle
+fn fetch(cmd: &Command, days: &[Day], fetcher: &Fetcher) -> bool {
+    let which: Vec<usize> = match cmd.days {
+        Days::All => (1..days.len()).collect(),
+        Days::One(d) => vec![d],
+    };
+    let mut ok = true;
+    for d in which {
+        if d == 0 {
+            eprintln!("Day 00 has no input to fetch");
+            ok = false;
+            continue;
+        }
+        match fetcher.input(d as u32) {
+            Ok(_) => println!("Day {:02}: {}", d, fetcher.cache_path(d as u32).display()),
+            Err(e) => {
+                eprintln!("Day {:02}: {}", d, e);
+                ok = false;
+            },
+        }
+    }
+
+    ok
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let days = days();
+    let cmd = match parse_args(&args, days.len()) {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        },
+    };
+    let fetcher = Fetcher::from_env();
+    let mut inputs = Resolver::standard(cmd.input.clone(), env!("CARGO_MANIFEST_DIR"));
+    inputs.add("input cache", fetcher.cache_dir().clone());
+    let ok = match cmd.mode {
+        Mode::Run => run(&cmd, &days, &inputs),
+        Mode::Fetch => fetch(&cmd, &days, &fetcher),
+        Mode::Bench => bench(&cmd, &days, &inputs),
+        Mode::Dot => dot(&cmd, &days, &inputs),
+        Mode::Verify => {
+            let file = cmd.answers.clone().unwrap_or_else(default_answers);
+            match Answers::load(&file) {
+                Ok(known) => verify(&cmd, &days, &inputs, &known),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    false
+                },
+            }
+        },
+    };
+    if !ok {
+        std::process::exit(1);
+    }
+}
+
+#[test]
+fn test() {
+    let args = |s: &str| -> Vec<String> { s.split(' ').map(|x| x.to_string()).collect() };
+    let a = parse_args(&args("run 18 --part 2"), 26);
+    assert_eq!(Ok(Command { part: Some(2), ..Command::new(Mode::Run, Days::One(18)) }), a);
+    let b = parse_args(&args("run all"), 26);
+    assert_eq!(Ok(Command::new(Mode::Run, Days::All)), b);
+    let c = parse_args(&args("run 07 -p 1 --input /tmp/aoc"), 26);
+    assert_eq!(Ok(Command { part: Some(1), input: Some(PathBuf::from("/tmp/aoc")), ..Command::new(Mode::Run, Days::One(7)) }), c);
+    assert!(parse_args(&args("run 26"), 26).is_err());
+    assert!(parse_args(&args("run 1 --part 3"), 26).is_err());
+    assert!(parse_args(&args("run 1 --part"), 26).is_err());
+    assert!(parse_args(&args("run 1 --input"), 26).is_err());
+    assert!(parse_args(&args("run all --input -"), 26).is_err());
+    let d = parse_args(&args("run 3 --input -"), 26);
+    assert_eq!(Ok(Command { input: Some(PathBuf::from("-")), ..Command::new(Mode::Run, Days::One(3)) }), d);
+    assert!(parse_args(&args("go 1"), 26).is_err());
+    let e = parse_args(&args("fetch all"), 26);
+    assert_eq!(Ok(Command::new(Mode::Fetch, Days::All)), e);
+    assert!(parse_args(&args("fetch 3 --part 1"), 26).is_err());
+    let f = parse_args(&args("verify all --answers /tmp/a.toml"), 26);
+    assert_eq!(Ok(Command { answers: Some(PathBuf::from("/tmp/a.toml")), ..Command::new(Mode::Verify, Days::All) }), f);
+    assert!(parse_args(&args("run all --answers /tmp/a.toml"), 26).is_err());
+    assert!(Answers::load(&default_answers()).is_ok());
+    let g = parse_args(&args("bench 15 --runs 5 --json"), 26);
+    assert_eq!(Ok(Command { runs: 5, json: true, ..Command::new(Mode::Bench, Days::One(15)) }), g);
+    assert!(parse_args(&args("bench 15 --runs 0"), 26).is_err());
+    assert!(parse_args(&args("run 15 --runs 5"), 26).is_err());
+    let h = parse_args(&args("run all --json"), 26);
+    assert_eq!(Ok(Command { json: true, ..Command::new(Mode::Run, Days::All) }), h);
+    assert!(parse_args(&args("verify all --json"), 26).is_err());
+    let i = parse_args(&args("dot 23 --input -"), 26);
+    assert_eq!(Ok(Command { input: Some(PathBuf::from("-")), ..Command::new(Mode::Dot, Days::One(23)) }), i);
+    assert!(parse_args(&args("dot 7"), 26).is_err());
+    assert!(parse_args(&args("dot all"), 26).is_err());
+    assert!(parse_args(&args("dot 18 --part 1"), 26).is_err());
+}
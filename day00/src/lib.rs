@@ -1,5 +1,7 @@
 
-extern crate proj_self;
+extern crate solution;
+
+use solution::Solution;
 
 enum KeyPress {
     Up,
@@ -20,7 +22,7 @@ enum Mark {
 }
 
 #[derive(Clone)]
-struct Point {
+pub struct Point {
     x: i32,
     y: i32,
     mark: Mark,
@@ -155,27 +157,32 @@ fn pair(points: &Vec<Point>) -> i32 {
     }
 }
 
-fn init(depth: u32) -> Vec<Point> {
-    let proj = proj_self::proj_dir(depth);
-    let file = proj.join("elvish_cheat_codes.txt");
-    let input = proj_self::file_to_str(&file);
-    get_points(&input)
-}
+pub struct Day00;
+
+impl Solution for Day00 {
+    const INPUT: &'static str = "elvish_cheat_codes.txt";
 
-fn main() {
-    let points = init(3);
-    let f = furthest(&points);
-    println!("Furthest: {}", f);
-    let p = pair(&points);
-    println!("Pair: {}", p);
+    type Input = Vec<Point>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Vec<Point> {
+        get_points(input)
+    }
+
+    fn part1(points: &Vec<Point>) -> i32 {
+        furthest(points)
+    }
+
+    fn part2(points: &Vec<Point>) -> i32 {
+        pair(points)
+    }
 }
 
 #[test]
 fn test() {
-    let points = init(4);
-    let f = furthest(&points);
-    assert_eq!(86, f);
-    let p = pair(&points);
-    assert_eq!(137, p);
+    let file = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(Day00::INPUT);
+    let answers = solution::solve_file::<Day00>(&file, &[1, 2]).unwrap();
+    assert_eq!(vec!["86", "137"], answers);
 }
 
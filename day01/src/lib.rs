@@ -1,5 +1,7 @@
 
-extern crate proj_self;
+extern crate solution;
+
+use solution::Solution;
 
 fn solve(bytes: &Vec<u8>, step: usize) -> u64 {
     bytes.iter().enumerate().fold(0u64, |acc, (i, val)| {
@@ -19,15 +21,24 @@ fn wide_captcha(input: &str) -> u64 {
     solve(&bytes, bytes.len() / 2)
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    println!("In: {}", input);
-    let answer = captcha(&input);
-    println!("Captcha {}", answer);
-    let answer2 = wide_captcha(&input);
-    println!("Captcha {}", answer2);
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = String;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> String {
+        input.to_string()
+    }
+
+    fn part1(input: &String) -> u64 {
+        captcha(input)
+    }
+
+    fn part2(input: &String) -> u64 {
+        wide_captcha(input)
+    }
 }
 
 #[test]
@@ -1,5 +1,7 @@
 
-extern crate proj_self;
+extern crate solution;
+
+use solution::Solution;
 
 fn div_checksum(lines: &Vec<Vec<u64>>) -> u64 {
     lines.iter().fold(0, |acc, x| acc + line_div(x))
@@ -8,7 +10,7 @@ fn div_checksum(lines: &Vec<Vec<u64>>) -> u64 {
 fn line_div(line: &Vec<u64>) -> u64 {
     let mut val = 0;
     'outer: for i in line.iter() {
-        'inner: for j in line.iter() {
+        for j in line.iter() {
             if i != j && i % j == 0 {
                 val = i / j;
                 break 'outer;
@@ -53,15 +55,24 @@ fn str_to_lines(input: &str) -> Vec<&str> {
         .collect()
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let vals = lines_to_vals(&str_to_lines(&input));
-    let c = checksum(&vals);
-    println!("Checksum: {}", c);
-    let c2 = div_checksum(&vals);
-    println!("Checksum2: {}", c2);
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Vec<u64>>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Vec<Vec<u64>> {
+        lines_to_vals(&str_to_lines(input))
+    }
+
+    fn part1(vals: &Vec<Vec<u64>>) -> u64 {
+        checksum(vals)
+    }
+
+    fn part2(vals: &Vec<Vec<u64>>) -> u64 {
+        div_checksum(vals)
+    }
 }
 
 #[test]
@@ -0,0 +1,100 @@
+
+extern crate grid;
+extern crate solution;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use grid::Direction::*;
+use solution::Solution;
+
+type Point = grid::Point<i64>;
+
+fn next(p: &Point, hs: &HashSet<Point>) -> Point{
+    let l = hs.contains(&p.step(Left));
+    let r = hs.contains(&p.step(Right));
+    let u = hs.contains(&p.step(Up));
+    let d = hs.contains(&p.step(Down));
+    if !l && !r && !u && !d {
+        p.step(Right)
+    } else if l && !u {
+        p.step(Up)
+    } else if d && !l {
+        p.step(Left)
+    } else if r && !d {
+        p.step(Down)
+    } else if u && !r {
+        p.step(Right)
+    } else {
+        *p
+    }
+}
+
+fn nth(n: i64) -> Point {
+    let mut ret = Point::origin();
+    let mut all: HashSet<Point> = HashSet::new();
+    all.insert(ret.clone());
+    let mut cur = 1;
+    while cur < n {
+        ret = next(&ret, &all);
+        all.insert(ret.clone());
+        cur += 1;
+    };
+    ret
+}
+
+fn hash_get(hm: &HashMap<Point, i64>, p: &Point) -> i64 {
+    match hm.get(p) {
+        Some(n) => *n,
+        None => 0,
+    }
+}
+
+fn adj(p: &Point, hm: &HashMap<Point, i64>) -> i64 {
+    p.neighbors8().iter().fold(hash_get(hm, p), |sum, n| sum + hash_get(hm, n))
+}
+
+fn more_than(n: i64) -> i64 {
+    let mut ret = Point::origin();
+    let mut all: HashSet<Point> = HashSet::new();
+    let mut hash: HashMap<Point, i64> = HashMap::new();
+    all.insert(ret.clone());
+    hash.insert(ret.clone(), 1);
+    let mut cur = hash_get(&hash, &ret);
+    while cur < n {
+        ret = next(&ret, &all);
+        all.insert(ret.clone());
+        let val = adj(&ret, &hash);
+        hash.insert(ret.clone(), val);
+        cur = hash_get(&hash, &ret);
+    };
+    cur
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = i64;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> i64 {
+        input.trim().parse().unwrap()
+    }
+
+    fn part1(n: &i64) -> i64 {
+        nth(*n).manhattan(&Point::origin())
+    }
+
+    fn part2(n: &i64) -> i64 {
+        more_than(*n)
+    }
+}
+
+#[test]
+fn test() {
+    let one = nth(325489);
+    let d = one.manhattan(&Point::origin());
+    assert_eq!(552, d);
+    let two = more_than(325489);
+    assert_eq!(330785, two);
+}
@@ -1,5 +1,8 @@
 
 extern crate proj_self;
+extern crate solution;
+
+use solution::Solution;
 
 fn line_to_words(input: &str) -> Vec<String> {
     let as_string = input.to_string();
@@ -24,7 +27,7 @@ fn is_anagram(a: &str, b: &str) -> bool {
     a_chars == b_chars
 }
 
-fn validate(input: &Vec<String>, func: &Fn(&str, &str) -> bool) -> bool{
+fn validate(input: &Vec<String>, func: &dyn Fn(&str, &str) -> bool) -> bool{
     let mut i = 0;
     while i < (input.len() - 1) {
         let mut j = i+1;
@@ -40,15 +43,24 @@ fn validate(input: &Vec<String>, func: &Fn(&str, &str) -> bool) -> bool{
     true
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let vals = proj_self::str_to_lines(&input);
-    let valid: Vec<&&str> = vals.iter().filter(|x| validate(&line_to_words(x), &is_equal)).collect();
-    println!("Valid: {}", valid.len());
-    let also_valid: Vec<&&str> = vals.iter().filter(|x| validate(&line_to_words(x), &is_anagram)).collect();
-    println!("Anagram Valid: {}", also_valid.len());
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<Vec<String>>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Vec<Vec<String>> {
+        proj_self::str_to_lines(input).iter().map(|x| line_to_words(x)).collect()
+    }
+
+    fn part1(phrases: &Vec<Vec<String>>) -> usize {
+        phrases.iter().filter(|x| validate(x, &is_equal)).count()
+    }
+
+    fn part2(phrases: &Vec<Vec<String>>) -> usize {
+        phrases.iter().filter(|x| validate(x, &is_anagram)).count()
+    }
 }
 
 #[test]
@@ -1,8 +1,11 @@
 
 extern crate proj_self;
+extern crate solution;
+
+use solution::Solution;
 
 #[derive(Debug)]
-struct World {
+pub struct World {
     jumps: Vec<i64>,
     pos: i64,
     solved: bool,
@@ -16,18 +19,18 @@ fn incdec(i: i64) -> i64 {
     if i >= 3 { i - 1 } else { i + 1 }
 }
 
-fn jump(w: &World, f: &Fn(i64) -> i64) -> World {
+fn jump(w: &World, f: &dyn Fn(i64) -> i64) -> World {
     if w.pos < 0 || w.pos >= (w.jumps.len() as i64) {
         return World { solved: true, pos: w.pos, jumps: w.jumps.clone() };
     }
-    let new_pos = w.pos + w.jumps[(w.pos as usize)];
+    let new_pos = w.pos + w.jumps[w.pos as usize];
     let mut new_vec = w.jumps.clone();
-    new_vec[(w.pos as usize)] = f(w.jumps[w.pos as usize]);
+    new_vec[w.pos as usize] = f(w.jumps[w.pos as usize]);
 
     World { solved: w.solved, pos: new_pos, jumps: new_vec }
 }
 
-fn solve(w: &World, f: &Fn(i64) -> i64) -> i64 {
+fn solve(w: &World, f: &dyn Fn(i64) -> i64) -> i64 {
     let mut counter = 0;
     let mut state = World { solved: w.solved, pos: w.pos, jumps: w.jumps.clone() };
     loop {
@@ -41,17 +44,26 @@ fn solve(w: &World, f: &Fn(i64) -> i64) -> i64 {
     counter - 1
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let vals = proj_self::str_to_lines(&input);
-    let ivals: Vec<i64> = vals.iter().map(|x| x.parse().unwrap()).collect();
-    let w = World { jumps: ivals, pos: 0, solved: false };
-    let steps = solve(&w, &inc);
-    println!("Steps: {}", steps);
-    let steps2 = solve(&w, &incdec);
-    println!("Steps: {}", steps2);
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = World;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> World {
+        let vals = proj_self::str_to_lines(input);
+        let ivals: Vec<i64> = vals.iter().map(|x| x.parse().unwrap()).collect();
+        World { jumps: ivals, pos: 0, solved: false }
+    }
+
+    fn part1(w: &World) -> i64 {
+        solve(w, &inc)
+    }
+
+    fn part2(w: &World) -> i64 {
+        solve(w, &incdec)
+    }
 }
 
 #[test]
@@ -1,11 +1,15 @@
 
+extern crate solution;
+
+use solution::Solution;
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
-struct Bank {
+pub struct Bank {
     blocks: u32,
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
-struct World {
+pub struct World {
     banks: Vec<Bank>,
 }
 
@@ -52,29 +56,27 @@ fn repeats(w: &World) -> (u64, u64) {
     (cycles, cycles - states[&state])
 }
 
-fn main() {
-    let init = vec![
-        Bank { blocks: 14 },
-        Bank { blocks: 0 },
-        Bank { blocks: 15 },
-        Bank { blocks: 12 },
-        Bank { blocks: 11 },
-        Bank { blocks: 11 },
-        Bank { blocks: 3 },
-        Bank { blocks: 5 },
-        Bank { blocks: 1 },
-        Bank { blocks: 6 },
-        Bank { blocks: 8 },
-        Bank { blocks: 4 },
-        Bank { blocks: 9 },
-        Bank { blocks: 1 },
-        Bank { blocks: 8 },
-        Bank { blocks: 4 },
-    ];
-    let w = World { banks: init };
-    let (cycle, len) = repeats(&w);
-    println!("Cycled at {}", cycle);
-    println!("Cycled length {}", len);
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = World;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> World {
+        let banks = input.split_whitespace().map(|x| Bank { blocks: x.parse().unwrap() }).collect();
+        World { banks: banks }
+    }
+
+    fn part1(w: &World) -> u64 {
+        let (cycle, _len) = repeats(w);
+        cycle
+    }
+
+    fn part2(w: &World) -> u64 {
+        let (_cycle, len) = repeats(w);
+        len
+    }
 }
 
 #[test]
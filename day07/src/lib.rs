@@ -1,8 +1,11 @@
 
 extern crate proj_self;
+extern crate solution;
+
+use solution::Solution;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct Program {
+pub struct Program {
     name: String,
     weight: u32,
     tower: Vec<Program>,
@@ -109,23 +112,47 @@ fn bottom(programs: &Vec<Program>) -> Program{
     new_program("")
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let vals = proj_self::str_to_lines(&input);
-    let programs = vals.iter().map(|x| str_to_program(x)).collect();
-    let b = bottom(&programs);
-    println!("Bottom: {}", b.name);
-    let weighed = set_all_weights(&programs);
-    let unbalanced: Vec<_> = weighed.iter().filter(|x| !balanced(x)).collect();
-    println!("Unbalanced nodes: {}", unbalanced.len());
-    for x in &unbalanced {
-        println!("{} is unbalanced", x.name);
-        for p in &x.tower {
-            println!("Node: {} Self: {}, Total: {}", p.name, p.weight, weight(&p));
+// the deepest unbalanced program has exactly one child
+// whose tower weighs something different from its siblings;
+// return the weight that child should have
+fn corrected_weight(programs: &Vec<Program>) -> u32 {
+    let weighed = set_all_weights(programs);
+    for p in weighed.iter().filter(|x| !balanced(x)) {
+        if !p.tower.iter().all(|x| balanced(x)) {
+            continue;
+        }
+        let totals: Vec<u32> = p.tower.iter().map(|x| weight(x)).collect();
+        for (i, x) in p.tower.iter().enumerate() {
+            let matches = totals.iter().filter(|t| **t == totals[i]).count();
+            if matches == 1 {
+                let target = if i == 0 { totals[1] } else { totals[0] };
+                return x.weight + target - totals[i];
+            }
         }
     }
+
+    0
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Vec<Program>;
+    type Part1 = String;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Vec<Program> {
+        let vals = proj_self::str_to_lines(input);
+        vals.iter().map(|x| str_to_program(x)).collect()
+    }
+
+    fn part1(programs: &Vec<Program>) -> String {
+        bottom(programs).name
+    }
+
+    fn part2(programs: &Vec<Program>) -> u32 {
+        corrected_weight(programs)
+    }
 }
 
 #[test]
@@ -158,4 +185,5 @@ fn test() {
     let unbalanced: Vec<_> = ws.iter().filter(|x| !balanced(x)).collect();
     assert_eq!(unbalanced.len(), 1);
     assert_eq!(weight(&unbalanced[0].tower[0]), 251);
+    assert_eq!(60, corrected_weight(&sample));
 }
@@ -1,5 +1,8 @@
 
 extern crate proj_self;
+extern crate solution;
+
+use solution::Solution;
 
 enum Operator {
     Eq,
@@ -28,7 +31,7 @@ struct Conditional {
     operand: i64,
 }
 
-struct Instruction {
+pub struct Instruction {
     register: String,
     inc: IncDec,
     amount: i64,
@@ -119,16 +122,65 @@ fn largest(cpu: &CPU) -> i64 {
     max
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let lines = proj_self::str_to_lines(&input);
-    let inst = lines.iter().map(|x| line_to_instruction(x)).collect();
-    let (result, profiled) = run(&inst);
-    let max = largest(&result);
-    println!("Current max register value: {}", max);
-    println!("Overall max register value: {}", profiled);
+fn operator(op: &Operator) -> &'static str {
+    match *op {
+        Operator::Eq => "==",
+        Operator::NotEq => "!=",
+        Operator::Gt => ">",
+        Operator::Gte => ">=",
+        Operator::Lt => "<",
+        Operator::Lte => "<=",
+        Operator::Invalid => "?",
+    }
+}
+
+// Graphviz for the program. Each instruction makes two basic
+// blocks: its test, which jumps to the update when it holds
+// and falls through to the next test when it doesn't, and
+// the update, which falls through to the next test too.
+pub fn dot(input: &str) -> String {
+    let inst = Day08::parse(input);
+    let mut lines = vec!["digraph cfg {".to_string(), "    node [shape=box, fontname=monospace];".to_string()];
+    for (n, i) in inst.iter().enumerate() {
+        let c = &i.condition;
+        let next = if n + 1 < inst.len() { format!("test{}", n + 1) } else { "exit".to_string() };
+        let verb = match i.inc {
+            IncDec::Inc => "inc",
+            IncDec::Dec => "dec",
+        };
+        lines.push(format!("    test{} [label=\"{}: if {} {} {}\"];", n, n, c.register, operator(&c.op), c.operand));
+        lines.push(format!("    update{} [label=\"{} {} {}\"];", n, i.register, verb, i.amount));
+        lines.push(format!("    test{} -> update{} [label=\"{} {} {}\"];", n, n, c.register, operator(&c.op), c.operand));
+        lines.push(format!("    test{} -> {} [style=dashed];", n, next));
+        lines.push(format!("    update{} -> {} [style=dashed];", n, next));
+    }
+    lines.push("    exit [shape=oval, label=halt];".to_string());
+    lines.push("}".to_string());
+
+    lines.join("\n") + "\n"
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Vec<Instruction>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Vec<Instruction> {
+        let lines = proj_self::str_to_lines(input);
+        lines.iter().map(|x| line_to_instruction(x)).collect()
+    }
+
+    fn part1(inst: &Vec<Instruction>) -> i64 {
+        let (result, _profiled) = run(inst);
+        largest(&result)
+    }
+
+    fn part2(inst: &Vec<Instruction>) -> i64 {
+        let (_result, profiled) = run(inst);
+        profiled
+    }
 }
 
 #[test]
@@ -144,4 +196,8 @@ fn test() {
     let max = largest(&result);
     assert_eq!(max, 1);
     assert_eq!(profiled, 10);
+
+    let graph = dot(&lines[..2].join("\n"));
+    assert!(graph.contains("    test0 [label=\"0: if a > 1\"];\n    update0 [label=\"b inc 5\"];\n"));
+    assert!(graph.contains("    test1 -> update1 [label=\"b < 5\"];\n    test1 -> exit [style=dashed];\n    update1 -> exit [style=dashed];\n"));
 }
@@ -1,5 +1,7 @@
 
-extern crate proj_self;
+extern crate solution;
+
+use solution::Solution;
 
 #[derive(Debug, Clone, Copy)]
 enum Sequence {
@@ -81,13 +83,26 @@ fn score_string(s: &str) -> (u64, u64) {
     (state.score, state.garbage)
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let (score, garbage) = score_string(&input.trim());
-    println!("Score: {}", score);
-    println!("Garbage: {}", garbage);
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = String;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> String {
+        input.trim().to_string()
+    }
+
+    fn part1(stream: &String) -> u64 {
+        let (score, _garbage) = score_string(stream);
+        score
+    }
+
+    fn part2(stream: &String) -> u64 {
+        let (_score, garbage) = score_string(stream);
+        garbage
+    }
 }
 
 #[test]
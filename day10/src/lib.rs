@@ -0,0 +1,39 @@
+
+extern crate knot_hash;
+extern crate solution;
+
+use knot_hash::KnotHashParams;
+use knot_hash::hash_string;
+use solution::Solution;
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = String;
+    type Part1 = u32;
+    type Part2 = String;
+
+    fn parse(input: &str) -> String {
+        input.trim().to_string()
+    }
+
+    fn part1(input: &String) -> u32 {
+        let lengths: Vec<u8> = input.split(",").map(|x| x.trim().parse().unwrap()).collect();
+        KnotHashParams::sparse().run(&lengths).unwrap().value()
+    }
+
+    fn part2(input: &String) -> String {
+        hash_string(input)
+    }
+}
+
+#[test]
+fn test() {
+    let s = KnotHashParams::sparse().size(5).run(&[3, 4, 1, 5]).unwrap();
+    assert_eq!(12, s.value());
+
+    assert_eq!("a2582a3a0e66e6e86e3812dcb672a272".to_string(), hash_string(""));
+    assert_eq!("33efeb34ea91902bb2f59c9920caa6cd".to_string(), hash_string("AoC 2017"));
+    assert_eq!("3efbe78a8d82f29979031a4aa0b16a9d".to_string(), hash_string("1,2,3"));
+    assert_eq!("63960835bcdc130f0b66d7ff4f6a5a8e".to_string(), hash_string("1,2,4"));
+}
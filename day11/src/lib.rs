@@ -1,13 +1,13 @@
 
-extern crate proj_self;
+extern crate grid;
+extern crate solution;
 
-#[derive(Eq, PartialEq)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+use solution::Solution;
+
+// hex cells, with y counting half rows
+type Point = grid::Point<i32>;
 
-enum Dir {
+pub enum Dir {
     North,
     NorthEast,
     SouthEast,
@@ -30,14 +30,14 @@ fn str_to_dir(s: &str) -> Dir {
 }
 
 fn next(p: &Point, d: &Dir) -> Point {
-    match *d {
-        Dir::North => Point { x: p.x, y: p.y + 2 },
-        Dir::NorthEast => Point { x: p.x + 1, y: p.y + 1 },
-        Dir::SouthEast => Point { x: p.x + 1, y: p.y - 1 },
-        Dir::South => Point { x: p.x, y: p.y - 2 },
-        Dir::SouthWest => Point { x: p.x - 1, y: p.y - 1 },
-        Dir::NorthWest => Point { x: p.x - 1, y: p.y + 1 },
-        Dir::Invalid => Point { x: p.x, y: p.y },
+    *p + match *d {
+        Dir::North => Point::new(0, 2),
+        Dir::NorthEast => Point::new(1, 1),
+        Dir::SouthEast => Point::new(1, -1),
+        Dir::South => Point::new(0, -2),
+        Dir::SouthWest => Point::new(-1, -1),
+        Dir::NorthWest => Point::new(-1, 1),
+        Dir::Invalid => Point::origin(),
     }
 }
 
@@ -46,7 +46,7 @@ fn diagonal(p1: &Point, p2: &Point) -> bool {
 }
 
 fn walk(p: &Point, dirs: &Vec<Dir>) -> (Point, i32) {
-    let mut ret = Point { x: p.x, y: p.y };
+    let mut ret = *p;
     let mut max = 0;
     for d in dirs {
         ret = next(&ret, &d);
@@ -71,8 +71,8 @@ fn shortest(p1: &Point, p2: &Point) -> i32 {
         return (p1.x - p2.x).abs();
     }
 
-    let mut vert = Point { x: p1.x, y: p1.y };
-    let mut diag = Point { x: p1.x, y: p1.y };
+    let mut vert = *p1;
+    let mut diag = *p1;
     let mut steps = 0;
     loop {
         if vert.y < p2.y {
@@ -106,22 +106,33 @@ fn shortest(p1: &Point, p2: &Point) -> i32 {
     }
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let steps = input.trim().split(",").map(|x| str_to_dir(x)).collect();
-    let origin = Point { x:0, y:0 };
-    let (dest, max) = walk(&origin, &steps);
-    println!("Finished at {}, {}", dest.x, dest.y);
-    let dist = shortest(&origin, &dest);
-    println!("Shortest path: {} steps", dist);
-    println!("Max distance: {} steps", max);
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Vec<Dir>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Vec<Dir> {
+        input.trim().split(",").map(|x| str_to_dir(x)).collect()
+    }
+
+    fn part1(steps: &Vec<Dir>) -> i32 {
+        let origin = Point::origin();
+        let (dest, _max) = walk(&origin, steps);
+        shortest(&origin, &dest)
+    }
+
+    fn part2(steps: &Vec<Dir>) -> i32 {
+        let origin = Point::origin();
+        let (_dest, max) = walk(&origin, steps);
+        max
+    }
 }
 
 #[test]
 fn test() {
-    let origin = Point { x:0, y:0 };
+    let origin = Point::origin();
     let a = "ne,ne,ne".split(",").map(|x| str_to_dir(x)).collect();
     let (b, _) = walk(&origin, &a);
     assert_eq!(3, shortest(&origin, &b));
@@ -1,8 +1,11 @@
 
 extern crate proj_self;
+extern crate solution;
+
 use std::collections::HashMap;
+use solution::Solution;
 
-struct Node {
+pub struct Node {
     id: u32,
     links: Vec<u32>,
 }
@@ -46,32 +49,47 @@ fn path_count(dest: u32, nodes: &HashMap<u32, Node>) -> Vec<u32> {
     reachable
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let lines = proj_self::str_to_lines(&input);
-    let nodes: Vec<Node> = lines.iter().map(|x| str_to_node(x)).collect();
-    let mut map = HashMap::new();
-    for n in nodes {
-        map.insert(n.id, n);
-    }
-    let all = path_count(0, &map);
-    println!("Paths to zero: {}", all.len());
-
+fn group_count(map: &HashMap<u32, Node>) -> usize {
     let mut groups = HashMap::new();
     let mut visited = HashMap::new();
     for (id, _node) in map.iter() {
         if !visited.contains_key(id) {
-            println!("Checking paths to {}", id);
-            let group = path_count(*id, &map);
+            let group = path_count(*id, map);
             for x in &group {
                 visited.insert(*x, true);
             }
             groups.insert(group, true);
         }
     }
-    println!("Groups: {}", groups.len());
+
+    groups.len()
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = HashMap<u32, Node>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> HashMap<u32, Node> {
+        let lines = proj_self::str_to_lines(input);
+        let nodes: Vec<Node> = lines.iter().map(|x| str_to_node(x)).collect();
+        let mut map = HashMap::new();
+        for n in nodes {
+            map.insert(n.id, n);
+        }
+
+        map
+    }
+
+    fn part1(map: &HashMap<u32, Node>) -> usize {
+        path_count(0, map).len()
+    }
+
+    fn part2(map: &HashMap<u32, Node>) -> usize {
+        group_count(map)
+    }
 }
 
 #[test]
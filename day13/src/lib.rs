@@ -1,5 +1,8 @@
 
 extern crate proj_self;
+extern crate solution;
+
+use solution::Solution;
 
 #[derive(Clone,Eq,PartialEq,Debug)]
 struct Layer {
@@ -122,8 +125,7 @@ fn safe_delay(lines: &Vec<&str>) -> i32 {
     let fw = lines_to_fw(lines);
     let layers: Vec<_> = fw.layers.iter().enumerate().filter(|&(_i, x)| x.range > 1).collect();
     'outer: loop {
-        println!("Try delay: {}", delay);
-        'inner: for &(i, layer) in layers.iter() {
+        for &(i, layer) in layers.iter() {
             if will_hit(layer, i, delay) {
                 delay += 1;
                 continue 'outer;
@@ -135,16 +137,25 @@ fn safe_delay(lines: &Vec<&str>) -> i32 {
     delay
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let lines = proj_self::str_to_lines(&input);
-    let fw = lines_to_fw(&lines);
-    let hits = count_damage(&fw, false);
-    println!("Damage: {}", hits);
-    let delay = safe_delay(&lines);
-    println!("Wait time: {}", delay);
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = String;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> String {
+        input.to_string()
+    }
+
+    fn part1(input: &String) -> i32 {
+        let lines = proj_self::str_to_lines(input);
+        count_damage(&lines_to_fw(&lines), false)
+    }
+
+    fn part2(input: &String) -> i32 {
+        safe_delay(&proj_self::str_to_lines(input))
+    }
 }
 
 #[test]
@@ -0,0 +1,78 @@
+
+extern crate knot_hash;
+extern crate grid;
+extern crate solution;
+
+use grid::Grid;
+use grid::Point;
+use knot_hash::Bitmap;
+use solution::Solution;
+
+#[derive(Eq, PartialEq)]
+enum Status {
+    Full,
+    Empty,
+    Marked,
+}
+
+fn new_region(s: &Status) -> bool {
+    match *s {
+        Status::Full => true,
+        _ => false,
+    }
+}
+
+fn is_region(grid: &mut Grid<Status>, p: Point) -> bool {
+    if !new_region(&grid[p]) {
+        return false;
+    }
+
+    grid.set(p, Status::Marked);
+    for n in grid.neighbors4(p) {
+        is_region(grid, n);
+    }
+
+    true
+}
+
+fn region_count(disk: &Bitmap) -> u32 {
+    let mut count = 0;
+    let mut grid = Grid::from_fn(Bitmap::WIDTH, disk.height(), |p: Point| {
+        if disk.get(p.x as usize, p.y as usize) { Status::Full } else { Status::Empty }
+    });
+    for p in grid.points() {
+        if is_region(&mut grid, p) {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = String;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> String {
+        input.trim().to_string()
+    }
+
+    fn part1(key: &String) -> u32 {
+        knot_hash::grid(key, 128).count_ones()
+    }
+
+    fn part2(key: &String) -> u32 {
+        region_count(&knot_hash::grid(key, 128))
+    }
+}
+
+#[test]
+fn test() {
+    let disk = knot_hash::grid("flqrgnkx", 128);
+    assert_eq!(8108, disk.count_ones());
+    let regions = region_count(&disk);
+    assert_eq!(1242, regions);
+}
@@ -1,4 +1,8 @@
 
+extern crate solution;
+
+use solution::Solution;
+
 #[derive(Debug)]
 struct Generator {
     prev: u64,
@@ -62,11 +66,30 @@ fn picky_matches(rounds: u64, seed_a: u64, seed_b: u64) -> u64 {
     matches
 }
 
-fn main() {
-    let pairs = count_matches(40000000, 722, 354);
-    println!("Matches: {}", pairs);
-    let pairs2 = picky_matches(5000000, 722, 354);
-    println!("Matches: {}", pairs2);
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = (u64, u64);
+    type Part1 = u64;
+    type Part2 = u64;
+
+    // "Generator A starts with 722"
+    fn parse(input: &str) -> (u64, u64) {
+        let seeds: Vec<u64> = input
+            .lines()
+            .filter_map(|x| x.split_whitespace().last())
+            .map(|x| x.parse().unwrap())
+            .collect();
+        (seeds[0], seeds[1])
+    }
+
+    fn part1(seeds: &(u64, u64)) -> u64 {
+        count_matches(40000000, seeds.0, seeds.1)
+    }
+
+    fn part2(seeds: &(u64, u64)) -> u64 {
+        picky_matches(5000000, seeds.0, seeds.1)
+    }
 }
 
 #[test]
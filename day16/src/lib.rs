@@ -1,7 +1,9 @@
 
-extern crate proj_self;
+extern crate solution;
 
-enum Move {
+use solution::Solution;
+
+pub enum Move {
     Spin(usize),
     Exchange(usize, usize),
     Partner(char, char),
@@ -92,36 +94,47 @@ fn as_str(chars: &Vec<char>) -> String {
     chars.iter().cloned().collect()
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let moves: Vec<Move> = input.split(",").map(|x| str_to_move(x)).collect();
-    let init = init_programs(16);
-    let end = dance(&init, &moves);
-    println!("Ending: {}", as_str(&end));
+fn dance_many(moves: &Vec<Move>) -> Programs {
     let mut many = init_programs(16);
     let mut map = std::collections::HashMap::new();
     let mut cycle = 0;
     for i in 0..1000000000 {
-        eprint!("{}\r", i);
         if map.contains_key(&many) {
-            println!("Cycled at {}", i);
             cycle = i;
             break;
         }
         map.insert(many.clone(), true);
-        many = dance(&many, &moves);
+        many = dance(&many, moves);
     }
     let mut next = cycle;
     while next + cycle < 1000000000 {
         next += cycle;
     }
-    println!("Resume at {}", next);
     for _i in next..1000000000 {
-        many = dance(&many, &moves);
+        many = dance(&many, moves);
+    }
+
+    many
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Vec<Move>;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Vec<Move> {
+        input.split(",").map(|x| str_to_move(x)).collect()
+    }
+
+    fn part1(moves: &Vec<Move>) -> String {
+        as_str(&dance(&init_programs(16), moves))
+    }
+
+    fn part2(moves: &Vec<Move>) -> String {
+        as_str(&dance_many(moves))
     }
-    println!("And then: {}", as_str(&many));
 }
 
 #[test]
@@ -1,4 +1,8 @@
 
+extern crate solution;
+
+use solution::Solution;
+
 #[derive(Debug)]
 struct SpinLock {
     pos: usize,
@@ -19,31 +23,50 @@ fn next(sp: &SpinLock, val: usize) -> SpinLock {
     SpinLock { pos: insert_pos+1, step: sp.step, buffer: new_buf }
 }
 
-fn main() {
-    let mut sp = SpinLock { pos: 0, step: 303, buffer: vec![0; 1] };
-    let mut second = 0;
+fn after_last(step: usize) -> usize {
+    let mut sp = SpinLock { pos: 0, step: step, buffer: vec![0; 1] };
     for i in 1..2018 {
         sp = next(&sp, i);
-        if second != sp.buffer[1] {
-            println!("1, 2: {}, {}", sp.buffer[0], sp.buffer[1]);
-            second = sp.buffer[1];
-        }
     }
-    println!("Next: {}", sp.buffer[sp.pos + 1]);
 
+    sp.buffer[sp.pos + 1]
+}
+
+fn next_to_zero(step: usize) -> usize {
     let mut len = 1;
     let mut pos = 0;
     let mut ans = 0;
     for i in 0..50000000 {
         let next_val = i + 1; 
-        let next_pos = ((pos + 303) % len) + 1;
+        let next_pos = ((pos + step) % len) + 1;
         if next_pos == 1 {
             ans = next_val;
         }
         pos = next_pos;
         len += 1;
     }
-    println!("Next to zero: {}", ans);
+
+    ans
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = usize;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> usize {
+        input.trim().parse().unwrap()
+    }
+
+    fn part1(step: &usize) -> usize {
+        after_last(*step)
+    }
+
+    fn part2(step: &usize) -> usize {
+        next_to_zero(*step)
+    }
 }
 
 #[test]
@@ -0,0 +1,98 @@
+extern crate duet_vm;
+extern crate solution;
+
+use duet_vm::cfg::Cfg;
+use duet_vm::network::Network;
+use duet_vm::network::Report;
+use duet_vm::network::Topology;
+use duet_vm::Dialect;
+use duet_vm::Event;
+use duet_vm::Machine;
+use duet_vm::Program;
+use solution::Solution;
+
+// the first sound recovered
+fn recover(program: &Program) -> i64 {
+    let mut machine = Machine::new(program, Dialect::Sound).expect("assembled programs only use a to z");
+    loop {
+        match machine.step() {
+            Event::Recovered(v) => return v,
+            Event::Halted => panic!("halted without recovering anything"),
+            Event::Faulted => panic!("mod by zero at pc {}", machine.pc()),
+            _ => (),
+        }
+    }
+}
+
+// programs 0 and 1, each sending to the other, run in turn
+// until neither can go on
+fn parallel(program: &Program) -> (Network, Report) {
+    let registers = vec![vec![('p', 0)], vec![('p', 1)]];
+    let mut network = Network::new(program, &registers, &Topology::Ring).unwrap();
+    let report = network.run();
+
+    (network, report)
+}
+
+// Graphviz for the program's control flow
+pub fn dot(input: &str) -> String {
+    let program = Day18::parse(input);
+    Cfg::new(&program).dot(&program)
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Program;
+    type Part1 = i64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Program {
+        duet_vm::assemble(input, Dialect::Sound).unwrap_or_else(|e| panic!("{}", duet_vm::asm::report(input, &e)))
+    }
+
+    fn part1(program: &Program) -> i64 {
+        recover(program)
+    }
+
+    fn part2(program: &Program) -> u64 {
+        let (_network, report) = parallel(program);
+        report.sent[1]
+    }
+}
+
+#[test]
+fn test() {
+    let lines = vec![
+        "set a 1",
+        "add a 2",
+        "mul a a",
+        "mod a 5",
+        "snd a",
+        "set a 0",
+        "rcv a",
+        "jgz a -1",
+        "set a 1",
+        "jgz a -2",
+    ];
+    assert_eq!(4, recover(&Day18::parse(&lines.join("\n"))));
+
+    let part2 = vec![
+        "snd 1",
+        "snd 2",
+        "snd p",
+        "rcv a",
+        "rcv b",
+        "rcv c",
+        "rcv d",
+    ];
+    let (network, report) = parallel(&Day18::parse(&part2.join("\n")));
+    assert_eq!(Report { sent: vec![3, 3], received: vec![3, 3], blocked: vec![0, 1], faulted: vec![] }, report);
+    let (a, b) = (&network.machines()[0], &network.machines()[1]);
+    assert_eq!((1, 0), (a.get('c'), b.get('c')));
+    assert_eq!((duet_vm::Status::Waiting, duet_vm::Status::Waiting), (a.status(), b.status()));
+
+    let graph = dot(&lines.join("\n"));
+    assert!(graph.contains("    pc7 [label=\"7: jgz a -1\\l\"];\n    pc7 -> pc6 [label=\"a > 0\"];\n    pc7 -> pc8 [style=dashed];\n"));
+    assert!(graph.contains("    pc8 -> pc7 [label=\"a > 0\"];\n    pc8 -> exit [style=dashed];\n"));
+}
@@ -0,0 +1,153 @@
+
+extern crate grid;
+extern crate solution;
+
+use grid::Direction;
+use grid::Grid;
+use grid::Point;
+use solution::Solution;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Tile {
+    Blank,
+    Vertical,
+    Horizontal,
+    Junction,
+    Letter(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Network {
+    tiles: Grid<Tile>,
+    pos: Point,
+    dir: Direction,
+    letters: String,
+}
+
+fn traverse(network: &Network) -> (String, u32) {
+    let mut net = network.clone();
+    let mut steps = 1;
+    loop {
+        let p = net.pos;
+
+        //println!("{:?} => {:?}", p, net.dir);
+
+        tick(&mut net);
+
+        if p == net.pos {
+            break;
+        }
+
+        steps += 1;
+
+        match net.tiles[net.pos] {
+            Tile::Letter(ref l) => {
+                net.letters.push_str(&l);
+            }
+            _ => (),
+        }
+    };
+
+    (net.letters, steps)
+}
+
+// whether the path continues out of p in direction dir
+fn leads(net: &Network, p: Point, dir: Direction) -> bool {
+    match net.tiles.get(p) {
+        Some(&Tile::Horizontal) => !dir.is_vertical(),
+        Some(&Tile::Vertical) => dir.is_vertical(),
+        Some(&Tile::Junction) | Some(&Tile::Letter(_)) => true,
+        _ => false,
+    }
+}
+
+fn not_blank(network: &Network, p: Point) -> bool {
+    match network.tiles.get(p) {
+        Some(&Tile::Blank) | None => false,
+        _ => true,
+    }
+}
+
+fn tick(network: &mut Network) {
+    match network.tiles[network.pos] {
+        Tile::Junction => {
+            let turns = if network.dir.is_vertical() {
+                [Direction::Left, Direction::Right]
+            } else {
+                [Direction::Up, Direction::Down]
+            };
+            for turn in turns.iter() {
+                let next = network.pos.step(*turn);
+                if leads(network, next, *turn) {
+                    network.pos = next;
+                    network.dir = *turn;
+                    break;
+                }
+            }
+        }
+        _ => {
+            let next = network.pos.step(network.dir);
+            if not_blank(network, next) {
+                network.pos = next;
+            }
+        }
+    }
+}
+
+fn char_to_tile(x: char) -> Tile {
+    match x {
+        '|' => Tile::Vertical,
+        '-' => Tile::Horizontal,
+        '+' => Tile::Junction,
+        'a' ..= 'z' => Tile::Letter(x.to_string()),
+        'A' ..= 'Z' => Tile::Letter(x.to_string()),
+        _ => Tile::Blank,
+    }
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Network;
+    type Part1 = String;
+    type Part2 = u32;
+
+    // the path starts at the only line on the top edge
+    fn parse(input: &str) -> Network {
+        let tiles = Grid::parse(input, char_to_tile);
+        let start = tiles.points().into_iter().find(|p: &Point| p.y == 0 && tiles[*p] == Tile::Vertical);
+        Network {
+            tiles: tiles,
+            pos: start.unwrap_or_else(Point::origin),
+            dir: Direction::Down,
+            letters: "".to_string(),
+        }
+    }
+
+    fn part1(world: &Network) -> String {
+        let (result, _steps) = traverse(world);
+        result
+    }
+
+    fn part2(world: &Network) -> u32 {
+        let (_result, steps) = traverse(world);
+        steps
+    }
+}
+
+#[test]
+fn test() {
+    let lines = vec![
+        "    |         ",
+        "    |  +--+   ",
+        "    A  |  C   ",
+        "F---|----E|--+",
+        "    |  |  |  D",
+        "    +B-+  +--+",
+    ];
+    let world = Day19::parse(&lines.join("\n"));
+    assert_eq!(world.pos, Point::new(4, 0));
+    let (result, steps) = traverse(&world);
+    assert_eq!(result, "ABCDEF");
+    assert_eq!(steps, 38);
+}
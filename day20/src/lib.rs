@@ -2,7 +2,10 @@
 #[macro_use] extern crate lazy_static;
 extern crate regex;
 extern crate proj_self;
+extern crate solution;
+
 use regex::Regex;
+use solution::Solution;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Point {
@@ -12,13 +15,14 @@ struct Point {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-struct Particle {
+pub struct Particle {
     position: Point,
     velocity: Point,
     acceleration: Point,
     collided: bool,
 }
 impl Particle {
+    #[allow(dead_code)]
     pub fn new(p: (i32,i32,i32), v: (i32,i32,i32), a: (i32,i32,i32)) -> Particle {
         let pos = Point { x: p.0, y: p.1, z: p.2 };
         let vel = Point { x: v.0, y: v.1, z: v.2 };
@@ -124,38 +128,42 @@ fn closest(particles: &Vec<Particle>) -> (Vec<usize>, Vec<usize>) {
     (closest, slowest)
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let lines = proj_self::str_to_lines(&input);
-    let mut particles: Vec<Particle> = lines.iter().map(|x| Particle::from_str(x)).collect();
-    let done;
-    loop {
-        particles = particles.iter().map(|x| tick(x)).collect();
-        let (close, slow) = closest(&particles);
-        if close.len() == 1 && slow.len() == 1 && close[0] == slow[0] {
-            done = close[0];
-            break;
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Vec<Particle>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Vec<Particle> {
+        let lines = proj_self::str_to_lines(input);
+        lines.iter().map(|x| Particle::from_str(x)).collect()
+    }
+
+    fn part1(start: &Vec<Particle>) -> usize {
+        let mut particles = start.clone();
+        loop {
+            particles = particles.iter().map(|x| tick(x)).collect();
+            let (close, slow) = closest(&particles);
+            if close.len() == 1 && slow.len() == 1 && close[0] == slow[0] {
+                return close[0];
+            }
         }
     }
-    println!("Particle {} is closest", done);
 
-    let mut parts2: Vec<Particle> = lines.iter().map(|x| Particle::from_str(x)).collect();
-    let mut steps = 0;
-    let all;
-    loop {
-        parts2 = parts2.iter().map(|x| tick(x)).collect();
-        mark_collisions(&mut parts2);
-        let remains = still_flying(&parts2);
-        steps += 1;
-        eprint!("{}\t{}\r", remains, steps);
-        if steps > 50000 {
-            all = remains;
-            break;
+    fn part2(start: &Vec<Particle>) -> usize {
+        let mut parts2 = start.clone();
+        let mut steps = 0;
+        loop {
+            parts2 = parts2.iter().map(|x| tick(x)).collect();
+            mark_collisions(&mut parts2);
+            let remains = still_flying(&parts2);
+            steps += 1;
+            if steps > 50000 {
+                return remains;
+            }
         }
     }
-    println!("Remaining: {}", all);
 }
 
 #[test]
@@ -0,0 +1,223 @@
+
+extern crate grid;
+extern crate proj_self;
+extern crate solution;
+
+use grid::Grid;
+use solution::Solution;
+
+pub struct Rule {
+    from: String,
+    to: String,
+}
+impl Rule {
+    #[allow(dead_code)]
+    pub fn new(f: &str, t: &str) -> Rule {
+        Rule { from: f.to_string(), to: t.to_string() }
+    }
+    
+    pub fn from_string(s: &str) -> Rule {
+        let parts: Vec<_> = s.split(" => ").collect();
+        Rule { from: parts[0].to_string(), to: parts[1].to_string() }
+    }
+}
+
+type Point = grid::Point<isize>;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Square {
+    tiles: Grid<bool>,
+}
+impl Square {
+    pub fn default() -> Square {
+        Square::from_rule(".#./..#/###")
+    }
+
+    pub fn from_rule(r: &str) -> Square {
+        Square { tiles: Grid::parse(&r.replace('/', "\n"), |c| c == '#') }
+    }
+
+    pub fn to_rule(&self) -> String {
+        let mut rule = String::new();
+        for (y, row) in self.tiles.rows().enumerate() {
+            if y > 0 {
+                rule.push('/');
+            }
+            rule.extend(row.iter().map(|t| if *t { '#' } else { '.' }));
+        }
+
+        rule
+    }
+
+    // n x n squares, row by row, back into one big square
+    pub fn from_squares(squares: &Vec<Square>) -> Square {
+        let per_row = (squares.len() as f64).sqrt() as usize;
+        let size = squares[0].size();
+        let n = size as isize;
+        let tiles = Grid::from_fn(per_row * size, per_row * size, |p: Point| {
+            let sq = &squares[(p.y / n) as usize * per_row + (p.x / n) as usize];
+            sq.tiles[Point::new(p.x % n, p.y % n)]
+        });
+
+        Square { tiles: tiles }
+    }
+
+    // a new square of the same size, cell p coming from from(p)
+    fn remap<F: Fn(Point, isize) -> Point>(&self, from: F) -> Square {
+        let n = self.size() as isize;
+        let tiles = Grid::from_fn(self.size(), self.size(), |p: Point| self.tiles[from(p, n - 1)]);
+
+        Square { tiles: tiles }
+    }
+
+    pub fn flip_v(&self) -> Square {
+        self.remap(|p, last| Point::new(p.x, last - p.y))
+    }
+
+    pub fn flip_h(&self) -> Square {
+        self.remap(|p, last| Point::new(last - p.x, p.y))
+    }
+
+    pub fn rotate_l(&self) -> Square {
+        self.remap(|p, last| Point::new(last - p.y, p.x))
+    }
+
+    #[allow(dead_code)]
+    pub fn rotate_r(&self) -> Square {
+        self.remap(|p, last| Point::new(p.y, last - p.x))
+    }
+
+    pub fn permute(&self) -> Vec<String> {
+        let mut ret = vec![
+            self.to_rule(),
+            self.flip_v().to_rule(),
+            self.flip_h().to_rule(),
+        ];
+        let mut rotator = self.clone();
+        for _i in 0..4 {
+            rotator = rotator.rotate_l();
+            ret.push(rotator.to_rule());
+            ret.push(rotator.flip_v().to_rule());
+            ret.push(rotator.flip_h().to_rule());
+        }
+
+        ret
+    }
+
+    #[allow(dead_code)]
+    pub fn rule_applies(&self, rule: &Rule) -> bool {
+        for str in self.permute() {
+            if str == rule.from {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn size(&self) -> usize {
+        self.tiles.width()
+    }
+
+    pub fn slice(&self, x: usize, y: usize, size: usize) -> Square {
+        let corner = Point::new(x as isize, y as isize);
+        let tiles = Grid::from_fn(size, size, |p: Point| self.tiles[corner + p]);
+
+        Square { tiles: tiles }
+    }
+
+    #[allow(dead_code)]
+    pub fn render(&self) {
+        println!("Rendering:");
+        println!("{}", self.tiles.render(|t| if *t { '#' } else { '.' }));
+    }
+
+    pub fn lights(&self) -> usize {
+        self.tiles.values().filter(|t| **t).count()
+    }
+}
+
+fn grow(sq: &Square, rules: &Vec<Rule>) -> Square {
+    let mut squares = vec![];
+    let slice_size = if sq.size() % 2 == 0 { 2 } else { 3 };
+    let mut y = 0;
+    loop {
+        let mut x = 0;
+        loop {
+            squares.push(sq.slice(x, y, slice_size));
+            x += slice_size;
+            if x >= sq.size() {
+                break;
+            }
+        }
+        y += slice_size;
+        if y >= sq.size() {
+            break;
+        }
+    }
+    for i in 0..squares.len() {
+        let permutations = squares[i].permute();
+        for rule in rules.iter() {
+            for p in permutations.iter() {
+                if *p == rule.from {
+                    squares[i] = Square::from_rule(&rule.to);
+                    break;
+                }
+            }
+        }
+    }
+
+    if squares.len() == 1 {
+        squares[0].clone()
+    } else {
+        Square::from_squares(&squares)
+    }
+}
+
+fn lights_after(rules: &Vec<Rule>, passes: usize) -> usize {
+    let mut sq = Square::default();
+    for _i in 0..passes {
+        sq = grow(&sq, rules);
+    }
+
+    sq.lights()
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Vec<Rule>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Vec<Rule> {
+        let lines = proj_self::str_to_lines(input);
+        lines.iter().map(|x| Rule::from_string(x)).collect()
+    }
+
+    fn part1(rules: &Vec<Rule>) -> usize {
+        lights_after(rules, 5)
+    }
+
+    fn part2(rules: &Vec<Rule>) -> usize {
+        lights_after(rules, 18)
+    }
+}
+
+#[test]
+fn test() {
+    let sq = Square::default();
+    sq.render();
+    let new_sq = Square::from_squares(&vec![sq.clone(), sq.clone(), sq.clone(), sq.clone()]);
+    new_sq.render();
+    let rules = vec![
+        Rule::new("../.#", "##./#../..."),
+        Rule::new(".#./..#/###", "#..#/..../..../#..#"),
+    ];
+    let mut growing = sq.clone();
+    for _i in 0..2 {
+        growing = grow(&growing, &rules);
+        growing.render();
+    }
+    assert_eq!(12, growing.lights());
+}
@@ -0,0 +1,169 @@
+
+extern crate grid;
+extern crate proj_self;
+extern crate solution;
+
+use grid::Direction;
+use grid::HashGrid;
+use grid::Point;
+use solution::Solution;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Status {
+    Clean,
+    Infected,
+    Weak,
+    Flagged,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Carrier {
+    pos: Point,
+    dir: Direction,
+    tally: usize,
+}
+impl Carrier {
+    pub fn new(x: i32, y: i32) -> Carrier {
+        Carrier { pos: Point::new(x, y), dir: Direction::Up, tally: 0 }
+    }
+
+    pub fn turn_left(&mut self) {
+        self.dir = self.dir.turn_left();
+    }
+
+    pub fn turn_right(&mut self) {
+        self.dir = self.dir.turn_right();
+    }
+
+    pub fn reverse(&mut self) {
+        self.dir = self.dir.reverse();
+    }
+
+    pub fn move_one(&mut self) {
+        self.pos = self.pos.step(self.dir);
+    }
+
+    pub fn inc(&mut self) {
+        self.tally += 1;
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Tiles {
+    tiles: HashGrid<i32, Status>,
+}
+impl Tiles {
+    pub fn new(lines: &Vec<&str>) -> Tiles {
+        let tiles = HashGrid::parse(&lines.join("\n"), Status::Clean, |c| {
+            match c {
+                '#' => Status::Infected,
+                _ => Status::Clean,
+            }
+        });
+
+        Tiles { tiles: tiles }
+    }
+
+    pub fn is_infected(&self, p: Point) -> bool {
+        *self.tiles.get(p) == Status::Infected
+    }
+
+    pub fn infect(&mut self, p: Point) {
+        self.tiles.set(p, Status::Infected);
+    }
+
+    pub fn clean(&mut self, p: Point) {
+        self.tiles.set(p, Status::Clean);
+    }
+
+    pub fn get_status(&self, p: Point) -> Status {
+        self.tiles.get(p).clone()
+    }
+
+    pub fn rotate_status(&mut self, p: Point) {
+        let next = match self.get_status(p) {
+            Status::Clean => Status::Weak,
+            Status::Weak => Status::Infected,
+            Status::Infected => Status::Flagged,
+            Status::Flagged => Status::Clean,
+        };
+        self.tiles.set(p, next);
+    }
+}
+
+fn bursts(t: &Tiles, c: &Carrier, i: usize) -> Carrier {
+    let mut tiles = t.clone();
+    let mut carrier = c.clone();
+    for _ in 0..i {
+        if tiles.is_infected(carrier.pos) {
+            tiles.clean(carrier.pos);
+            carrier.turn_right();
+        } else {
+            tiles.infect(carrier.pos);
+            carrier.turn_left();
+            carrier.inc();
+        }
+        carrier.move_one();
+    }
+
+    carrier
+}
+
+fn bursts2(t: &Tiles, c: &Carrier, i: usize) -> Carrier {
+    let mut tiles = t.clone();
+    let mut carrier = c.clone();
+    for _i in 0..i {
+        match tiles.get_status(carrier.pos) {
+            Status::Clean => carrier.turn_left(),
+            Status::Infected => carrier.turn_right(),
+            Status::Weak => carrier.inc(),
+            Status::Flagged => carrier.reverse(),
+        };
+        tiles.rotate_status(carrier.pos);
+        carrier.move_one();
+    }
+
+    carrier
+}
+
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = (Tiles, Carrier);
+    type Part1 = usize;
+    type Part2 = usize;
+
+    // the carrier starts in the middle of the map
+    fn parse(input: &str) -> (Tiles, Carrier) {
+        let lines = proj_self::str_to_lines(input);
+        let carrier = Carrier::new((lines[0].len() / 2) as i32, (lines.len() / 2) as i32);
+        (Tiles::new(&lines), carrier)
+    }
+
+    fn part1(input: &(Tiles, Carrier)) -> usize {
+        bursts(&input.0, &input.1, 10000).tally
+    }
+
+    fn part2(input: &(Tiles, Carrier)) -> usize {
+        bursts2(&input.0, &input.1, 10000000).tally
+    }
+}
+
+#[test]
+fn test() {
+    let lines = vec![
+        "..#",
+        "#..",
+        "...",
+    ];
+    let tiles = Tiles::new(&lines);
+    let carrier = Carrier::new(1, 1);
+    let after7 = bursts(&tiles, &carrier, 7);
+    assert_eq!(after7.tally, 5);
+    let after70 = bursts(&tiles, &carrier, 70);
+    assert_eq!(after70.tally, 41);
+
+    let after100 = bursts2(&tiles, &carrier, 100);
+    assert_eq!(after100.tally, 26);
+}
@@ -0,0 +1,46 @@
+extern crate duet_vm;
+extern crate solution;
+
+use duet_vm::cfg::Cfg;
+use duet_vm::Dialect;
+use duet_vm::Machine;
+use duet_vm::Op;
+use duet_vm::Program;
+use solution::Solution;
+
+fn parse_isa(input: &str) -> Program {
+    duet_vm::assemble(input, Dialect::Coprocessor).unwrap_or_else(|e| panic!("{}", duet_vm::asm::report(input, &e)))
+}
+
+// Graphviz for the program's control flow
+pub fn dot(input: &str) -> String {
+    let isa = parse_isa(input);
+    Cfg::new(&isa).dot(&isa)
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Program;
+    type Part1 = u64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Program {
+        parse_isa(input)
+    }
+
+    fn part1(isa: &Program) -> u64 {
+        let mut coproc = Machine::new(isa, Dialect::Coprocessor).expect("assembled programs only use a to z");
+        coproc.run();
+        coproc.executed(Op::Mul)
+    }
+
+    fn part2(isa: &Program) -> i64 {
+        // the prime checking double loop would take forever
+        let isa2 = duet_vm::optimize::optimize(isa);
+        let mut coproc2 = Machine::new(&isa2, Dialect::Coprocessor).expect("assembled programs only use a to z");
+        coproc2.set('a', 1).unwrap();
+        coproc2.run();
+        coproc2.get('h')
+    }
+}
@@ -1,8 +1,11 @@
 
 extern crate proj_self;
+extern crate solution;
+
+use solution::Solution;
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
-struct Component {
+pub struct Component {
     p1: usize,
     link1: bool,
     p2: usize,
@@ -72,6 +75,7 @@ impl Bridge {
         ttl
     }
 
+    #[allow(dead_code)]
     pub fn print(&self) {
         for c in self.spans.iter() {
             print!("{}/{}--", c.p1, c.p2); 
@@ -139,18 +143,25 @@ fn longest(bridges: &Vec<Bridge>) -> Vec<Bridge> {
     ret
 }
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let lines = proj_self::str_to_lines(&input);
+pub struct Day24;
 
-    let comp: Vec<Component> = lines.iter().map(|x| Component::from_str(x)).collect();
-    let all = all_bridges(&comp);
-    println!("{} options, strongest is {}", all.len(), strongest(&all));
+impl Solution for Day24 {
+    type Input = Vec<Component>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    let long = longest(&all);
-    println!("Longest ({}), Strongest ({})", long[0].spans.len(), strongest(&long));
+    fn parse(input: &str) -> Vec<Component> {
+        let lines = proj_self::str_to_lines(input);
+        lines.iter().map(|x| Component::from_str(x)).collect()
+    }
+
+    fn part1(comp: &Vec<Component>) -> usize {
+        strongest(&all_bridges(comp))
+    }
+
+    fn part2(comp: &Vec<Component>) -> usize {
+        strongest(&longest(&all_bridges(comp)))
+    }
 }
 
 #[test]
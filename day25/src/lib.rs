@@ -1,11 +1,16 @@
 
 extern crate proj_self;
+extern crate solution;
+
+use std::collections::HashMap;
+use solution::NoAnswer;
+use solution::Solution;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
-enum Dir { L, R }
+pub enum Dir { L, R }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
-struct Step {
+pub struct Step {
     write: u8,
     shift: Dir,
     next: char,
@@ -101,12 +106,13 @@ impl Turing {
     pub fn checksum(&self) -> u64 {
         let mut ttl = 0;
         for (_state, val) in &self.tape {
-            ttl += (*val as u64);
+            ttl += *val as u64;
         }
 
         ttl
     }
 
+    #[allow(dead_code)]
     pub fn print(&self) {
         for i in -10..11 {
             print!("{} ", self.get(&i));
@@ -123,22 +129,30 @@ impl Turing {
     }
 }
 
+pub struct Day25;
 
-fn main() {
-    let proj = proj_self::proj_dir(3);
-    let file = proj.join("input.txt");
-    let input = proj_self::file_to_str(&file);
-    let lines = proj_self::str_to_lines(&input);
+impl Solution for Day25 {
+    type Input = (char, u64, HashMap<char, (Step, Step)>);
+    type Part1 = u64;
+    type Part2 = NoAnswer;
 
-    let (init, after, states) = Step::factory(&lines);
-    let mut turing = Turing::new(&init, &states);
-    for i in 0..after {
-        if i % 5000 == 0 {
-            eprint!("{}\r", i);
+    fn parse(input: &str) -> (char, u64, HashMap<char, (Step, Step)>) {
+        Step::factory(&proj_self::str_to_lines(input))
+    }
+
+    fn part1(blueprint: &(char, u64, HashMap<char, (Step, Step)>)) -> u64 {
+        let (ref init, after, ref states) = *blueprint;
+        let mut turing = Turing::new(init, states);
+        for _ in 0..after {
+            turing.step();
         }
-        turing.step();
+        turing.checksum()
+    }
+
+    // there is no second puzzle on the last day
+    fn part2(_blueprint: &(char, u64, HashMap<char, (Step, Step)>)) -> NoAnswer {
+        NoAnswer
     }
-    println!("Checksum: {}", turing.checksum());
 }
 
 #[test]
@@ -0,0 +1,223 @@
+
+use std::fmt;
+use Dialect;
+use Instr;
+use Op;
+use Operand;
+use Program;
+use Slot;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsmErrorKind {
+    UnknownOpcode,
+    // an opcode the program's dialect doesn't have
+    WrongDialect(Dialect),
+    // operands expected and found
+    Arity(usize, usize),
+    InvalidRegister,
+    InvalidOperand,
+}
+
+// Where and why a line didn't assemble. Lines and
+// columns count from 1, columns in characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub kind: AsmErrorKind,
+}
+
+impl AsmError {
+    pub fn message(&self) -> String {
+        match self.kind {
+            AsmErrorKind::UnknownOpcode => format!("unknown opcode `{}`", self.token),
+            AsmErrorKind::WrongDialect(d) => format!("`{}` isn't in the {} dialect", self.token, d.name()),
+            AsmErrorKind::Arity(want, got) => {
+                format!("`{}` takes {} operand{}, found {}", self.token, want, if want == 1 { "" } else { "s" }, got)
+            },
+            AsmErrorKind::InvalidRegister => format!("`{}` isn't a register (a through z)", self.token),
+            AsmErrorKind::InvalidOperand => format!("`{}` is neither a register nor a number", self.token),
+        }
+    }
+
+    // the message plus the offending line, underlined
+    pub fn render(&self, src: &str) -> String {
+        let line = src.lines().nth(self.line - 1).unwrap_or("");
+        let pad: String = line.chars().take(self.column - 1).map(|c| if c == '\t' { '\t' } else { ' ' }).collect();
+        let marks = "^".repeat(self.token.chars().count().max(1));
+
+        format!("{}\n    {}\n    {}{}", self, line, pad, marks)
+    }
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message())
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+// every error rendered against the source, for panics and stderr
+pub fn report(src: &str, errors: &[AsmError]) -> String {
+    let rendered: Vec<String> = errors.iter().map(|e| e.render(src)).collect();
+    rendered.join("\n")
+}
+
+// (column, token) for each whitespace separated token
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut ret = vec![];
+    let mut start = None;
+    for (col, (i, c)) in line.char_indices().enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((col, i)),
+            (true, Some((scol, si))) => {
+                ret.push((scol + 1, &line[si..i]));
+                start = None;
+            },
+            _ => (),
+        }
+    }
+    if let Some((scol, si)) = start {
+        ret.push((scol + 1, &line[si..]));
+    }
+
+    ret
+}
+
+fn register(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_lowercase() => Some(c),
+        _ => None,
+    }
+}
+
+fn operand(s: &str) -> Option<Operand> {
+    match s.parse() {
+        Ok(v) => Some(Operand::Value(v)),
+        Err(_e) => register(s).map(Operand::Register),
+    }
+}
+
+fn assemble_line(num: usize, line: &str, dialect: Option<Dialect>) -> Result<Option<Instr>, Vec<AsmError>> {
+    let toks = tokens(line);
+    let err = |&(col, tok): &(usize, &str), kind: AsmErrorKind| AsmError { line: num, column: col, token: tok.to_string(), kind };
+    let (first, args) = match toks.split_first() {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let op = match Op::from_name(first.1) {
+        Some(op) => op,
+        None => return Err(vec![err(first, AsmErrorKind::UnknownOpcode)]),
+    };
+    if let Some(d) = dialect {
+        if !d.ops().contains(&op) {
+            return Err(vec![err(first, AsmErrorKind::WrongDialect(d))]);
+        }
+    }
+    let slots = op.slots();
+    let want = slots.len();
+    if args.len() != want {
+        return Err(vec![err(first, AsmErrorKind::Arity(want, args.len()))]);
+    }
+
+    let mut errors = vec![];
+    let mut operands = vec![];
+    for (arg, slot) in args.iter().zip(slots) {
+        if *slot == Slot::Register {
+            match register(arg.1) {
+                Some(r) => operands.push(Operand::Register(r)),
+                None => errors.push(err(arg, AsmErrorKind::InvalidRegister)),
+            }
+        } else {
+            match operand(arg.1) {
+                Some(x) => operands.push(x),
+                None => errors.push(err(arg, AsmErrorKind::InvalidOperand)),
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(Instr::new(op, &operands))
+}
+
+// One instruction per line; blank lines are skipped.
+// Every bad line is reported, not just the first, and
+// so is any opcode the dialect doesn't have.
+pub fn assemble(src: &str, dialect: Dialect) -> Result<Program, Vec<AsmError>> {
+    assemble_with(src, Some(dialect))
+}
+
+// Any opcode at all, macro-instructions included, for
+// reading back what optimize wrote
+pub fn assemble_any(src: &str) -> Result<Program, Vec<AsmError>> {
+    assemble_with(src, None)
+}
+
+fn assemble_with(src: &str, dialect: Option<Dialect>) -> Result<Program, Vec<AsmError>> {
+    let mut program = vec![];
+    let mut errors = vec![];
+    for (i, line) in src.lines().enumerate() {
+        match assemble_line(i + 1, line, dialect) {
+            Ok(Some(instr)) => program.push(instr),
+            Ok(None) => (),
+            Err(mut e) => errors.append(&mut e),
+        }
+    }
+
+    if errors.is_empty() { Ok(program) } else { Err(errors) }
+}
+
+#[cfg(test)]
+mod tests {
+    use asm::AsmError;
+    use asm::AsmErrorKind;
+    use asm::assemble;
+    use asm::assemble_any;
+    use asm::report;
+    use Dialect;
+    use Instr;
+    use Operand;
+
+    #[test]
+    fn it_works() {
+        let ok = assemble("set a 1\n\n  jgz a -2\nsnd 5\n", Dialect::Duet).unwrap();
+        assert_eq!(vec![
+            Instr::Set('a', Operand::Value(1)),
+            Instr::Jgz(Operand::Register('a'), Operand::Value(-2)),
+            Instr::Snd(Operand::Value(5)),
+        ], ok);
+
+        let src = "set a 1\nnop a\nadd a\nmul 3 x1\n\tjgz a b c\nrcv";
+        let errors = assemble(src, Dialect::Sound).unwrap_err();
+        let err = |line, column, token: &str, kind| AsmError { line, column, token: token.to_string(), kind };
+        assert_eq!(vec![
+            err(2, 1, "nop", AsmErrorKind::UnknownOpcode),
+            err(3, 1, "add", AsmErrorKind::Arity(2, 1)),
+            err(4, 5, "3", AsmErrorKind::InvalidRegister),
+            err(4, 7, "x1", AsmErrorKind::InvalidOperand),
+            err(5, 2, "jgz", AsmErrorKind::Arity(2, 3)),
+            err(6, 1, "rcv", AsmErrorKind::Arity(1, 0)),
+        ], errors);
+        assert_eq!("line 4, column 7: `x1` is neither a register nor a number", errors[3].to_string());
+        assert_eq!("line 6, column 1: `rcv` takes 1 operand, found 0", errors[5].to_string());
+        assert_eq!("line 4, column 5: `3` isn't a register (a through z)\n    mul 3 x1\n        ^", errors[2].render(src));
+        assert_eq!(6, report(src, &errors).lines().filter(|l| l.starts_with("line")).count());
+
+        // day 18 and day 23 don't share opcodes, and only optimize makes macros
+        let errors = assemble("set a 1\njnz a 2\n  sub a 1\nmac a 2 c", Dialect::Duet).unwrap_err();
+        assert_eq!(vec![
+            err(2, 1, "jnz", AsmErrorKind::WrongDialect(Dialect::Duet)),
+            err(3, 3, "sub", AsmErrorKind::WrongDialect(Dialect::Duet)),
+            err(4, 1, "mac", AsmErrorKind::WrongDialect(Dialect::Duet)),
+        ], errors);
+        assert_eq!("line 2, column 1: `jnz` isn't in the duet dialect", errors[0].to_string());
+        let errors = assemble("set b 1\nsnd b\nrcv b\nmul b b", Dialect::Coprocessor).unwrap_err();
+        assert_eq!(vec![(2, "snd"), (3, "rcv")], errors.iter().map(|e| (e.line, e.token.as_str())).collect::<Vec<_>>());
+        assert_eq!(4, assemble_any("set b 1\nsnd b\njnz b 2\nfct f d e b").unwrap().len());
+    }
+}
@@ -0,0 +1,120 @@
+
+extern crate duet_vm;
+
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use duet_vm::debug::Debugger;
+use duet_vm::Dialect;
+use duet_vm::Machine;
+
+#[derive(Debug, Eq, PartialEq)]
+struct Options {
+    dialect: Dialect,
+    registers: Vec<(char, i64)>,
+    optimized: bool,
+    file: String,
+}
+
+fn usage() -> String {
+    [
+        "Usage: duet-dbg [--sound | --coprocessor] [--optimize] [--set R=V]... FILE",
+        "",
+        "Steps through a day 18 or day 23 program. Programs use day 18's",
+        "send and receive by default; --sound plays and recovers instead,",
+        "and --coprocessor takes day 23's instructions.",
+        "--set pokes a register before the first instruction. --optimize",
+        "fuses the loops it recognizes into macro-instructions first.",
+        "Type help at the prompt for commands; an empty line repeats the",
+        "last one.",
+    ].join("\n")
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut dialect = Dialect::Duet;
+    let mut registers = vec![];
+    let mut file = None;
+    let mut optimized = false;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--sound" => dialect = Dialect::Sound,
+            "--duet" => dialect = Dialect::Duet,
+            "--coprocessor" => dialect = Dialect::Coprocessor,
+            "--optimize" | "-O" => optimized = true,
+            "--set" => {
+                i += 1;
+                registers.push(duet_vm::parse_assignment(args.get(i).map(|x| x.as_str()).unwrap_or(""))?);
+            },
+            "--help" | "-h" => return Err(usage()),
+            x if x.starts_with("--") => return Err(format!("Unexpected argument: {}\n{}", x, usage())),
+            x if file.is_none() => file = Some(x.to_string()),
+            x => return Err(format!("Only one program at a time: {}", x)),
+        }
+        i += 1;
+    }
+
+    match file {
+        Some(f) => Ok(Options { dialect, registers, optimized, file: f }),
+        None => Err(usage()),
+    }
+}
+
+fn run(opts: &Options) -> Result<(), String> {
+    let src = fs::read_to_string(&opts.file).map_err(|e| format!("{}: {}", opts.file, e))?;
+    let program = duet_vm::assemble(&src, opts.dialect).map_err(|e| duet_vm::asm::report(&src, &e))?;
+    let program = if opts.optimized { duet_vm::optimize::optimize(&program) } else { program };
+    let mut machine = Machine::new(&program, opts.dialect)?;
+    for &(r, v) in &opts.registers {
+        machine.set(r, v)?;
+    }
+    let mut dbg = Debugger::new(machine);
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let io_err = |e: io::Error| e.to_string();
+    dbg.exec("list 3", &mut out).map_err(io_err)?;
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut last = String::new();
+    loop {
+        write!(out, "(duet) ").and_then(|_| out.flush()).map_err(io_err)?;
+        let line = match lines.next() {
+            Some(line) => line.map_err(io_err)?,
+            None => break,
+        };
+        if !line.trim().is_empty() {
+            last = line;
+        }
+        if !dbg.exec(&last, &mut out).map_err(io_err)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = parse_args(&args).and_then(|opts| run(&opts));
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+#[test]
+fn test() {
+    let args = |s: &str| -> Vec<String> { s.split(' ').map(|x| x.to_string()).collect() };
+    let a = parse_args(&args("--sound input.txt")).unwrap();
+    assert_eq!(Options { dialect: Dialect::Sound, registers: vec![], optimized: false, file: "input.txt".to_string() }, a);
+    let b = parse_args(&args("--set a=1 input.txt -O --set h=-2")).unwrap();
+    assert_eq!((Dialect::Duet, vec![('a', 1), ('h', -2)], true), (b.dialect, b.registers, b.optimized));
+    assert_eq!(Dialect::Coprocessor, parse_args(&args("--coprocessor input.txt")).unwrap().dialect);
+    assert!(parse_args(&args("--set a input.txt")).is_err());
+    assert!(parse_args(&args("--set ab=1 input.txt")).is_err());
+    assert!(parse_args(&args("--set")).is_err());
+    assert!(parse_args(&args("one.txt two.txt")).is_err());
+    assert!(parse_args(&[]).is_err());
+}
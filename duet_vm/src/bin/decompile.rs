@@ -0,0 +1,78 @@
+
+extern crate duet_vm;
+
+use std::fs;
+use duet_vm::decompile;
+use duet_vm::Dialect;
+
+#[derive(Debug, Eq, PartialEq)]
+struct Options {
+    dialect: Dialect,
+    rust: bool,
+    optimized: bool,
+    file: String,
+}
+
+fn usage() -> String {
+    [
+        "Usage: duet-decompile [--sound | --coprocessor] [--optimize] [--rust] FILE",
+        "",
+        "Prints a day 18 or day 23 program as structured pseudo-code,",
+        "with its loops and ifs recovered from the jumps. --rust prints a",
+        "Rust function instead, when the program has no gotos left over.",
+        "--sound reads snd and rcv as day 18's sounds, --coprocessor takes",
+        "day 23's instructions; --optimize fuses the loops it recognizes",
+        "into macro-instructions first.",
+    ].join("\n")
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut opts = Options { dialect: Dialect::Duet, rust: false, optimized: false, file: String::new() };
+    let mut file = None;
+    for arg in args {
+        match arg.as_str() {
+            "--sound" => opts.dialect = Dialect::Sound,
+            "--duet" => opts.dialect = Dialect::Duet,
+            "--coprocessor" => opts.dialect = Dialect::Coprocessor,
+            "--rust" => opts.rust = true,
+            "--optimize" | "-O" => opts.optimized = true,
+            "--help" | "-h" => return Err(usage()),
+            x if x.starts_with("--") => return Err(format!("Unexpected argument: {}\n{}", x, usage())),
+            x if file.is_none() => file = Some(x.to_string()),
+            x => return Err(format!("Only one program at a time: {}", x)),
+        }
+    }
+
+    opts.file = file.ok_or_else(usage)?;
+    Ok(opts)
+}
+
+fn run(opts: &Options) -> Result<String, String> {
+    let src = fs::read_to_string(&opts.file).map_err(|e| format!("{}: {}", opts.file, e))?;
+    let program = duet_vm::assemble(&src, opts.dialect).map_err(|e| duet_vm::asm::report(&src, &e))?;
+    let program = if opts.optimized { duet_vm::optimize::optimize(&program) } else { program };
+
+    if opts.rust { decompile::rust(&program, opts.dialect) } else { Ok(decompile::pseudo(&program, opts.dialect)) }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match parse_args(&args).and_then(|opts| run(&opts)) {
+        Ok(text) => print!("{}", text),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        },
+    }
+}
+
+#[test]
+fn test() {
+    let args = |s: &str| -> Vec<String> { s.split(' ').map(|x| x.to_string()).collect() };
+    let a = parse_args(&args("--rust -O input.txt")).unwrap();
+    assert_eq!(Options { dialect: Dialect::Duet, rust: true, optimized: true, file: "input.txt".to_string() }, a);
+    assert_eq!(Dialect::Sound, parse_args(&args("input.txt --sound")).unwrap().dialect);
+    assert!(parse_args(&args("--c input.txt")).is_err());
+    assert!(parse_args(&args("one.txt two.txt")).is_err());
+    assert!(parse_args(&[]).is_err());
+}
@@ -0,0 +1,298 @@
+
+extern crate duet_vm;
+
+use std::fs;
+use std::fs::File;
+use duet_vm::trace;
+use duet_vm::trace::Divergence;
+use duet_vm::trace::Step;
+use duet_vm::trace::Trace;
+use duet_vm::trace::TraceWriter;
+use duet_vm::Dialect;
+use duet_vm::Event;
+use duet_vm::Machine;
+use duet_vm::Status;
+
+#[derive(Debug, Eq, PartialEq)]
+struct Recording {
+    dialect: Dialect,
+    registers: Vec<(char, i64)>,
+    mailbox: Vec<i64>,
+    limit: Option<u64>,
+    optimized: bool,
+    json: bool,
+    program: String,
+    out: String,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Command {
+    Record(Recording),
+    Show { trace: String, at: usize, count: usize },
+    Diff { a: String, b: String, only: Vec<char> },
+}
+
+fn usage() -> String {
+    [
+        "Usage: duet-trace record [--sound | --coprocessor] [--optimize] [--set R=V]... [--send V]... [--limit N] [--json] PROGRAM OUT",
+        "       duet-trace show TRACE [--at STEP] [--count N]",
+        "       duet-trace diff A B [--only REGISTERS]",
+        "",
+        "record runs PROGRAM until it halts, waits on an empty mailbox or",
+        "reaches the step limit, and writes every step to OUT, compactly or",
+        "as JSON lines. Programs use day 18's send and receive unless",
+        "--sound or --coprocessor (day 23) says otherwise. --send queues",
+        "values for rcv beforehand; --optimize fuses the loops it",
+        "recognizes first.",
+        "show prints the registers before STEP and the N steps from there.",
+        "diff finds the first step where two traces disagree; with --only,",
+        "just whether the listed registers (e.g. h or b,h) took on the same",
+        "values in the same order.",
+    ].join("\n")
+}
+
+fn number<T: std::str::FromStr>(args: &[String], i: usize, flag: &str) -> Result<T, String> {
+    args.get(i).and_then(|x| x.parse().ok()).ok_or_else(|| format!("{} needs a number", flag))
+}
+
+// "b,h"
+fn parse_registers(s: &str) -> Result<Vec<char>, String> {
+    let one = |r: &str| match r.as_bytes() {
+        &[c @ b'a'..=b'z'] => Some(c as char),
+        _ => None,
+    };
+    s.split(',').map(one).collect::<Option<Vec<char>>>().ok_or_else(|| format!("--only wants registers like b,h, not {}", s))
+}
+
+// the subcommand a flag belongs to
+fn flag_command(flag: &str) -> Option<&'static str> {
+    match flag {
+        "--sound" | "--coprocessor" | "--set" | "--send" | "--limit" | "--optimize" | "-O" | "--json" => Some("record"),
+        "--at" | "--count" | "-n" => Some("show"),
+        "--only" => Some("diff"),
+        _ => None,
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (mut dialect, mut registers, mut mailbox, mut limit) = (Dialect::Duet, vec![], vec![], None);
+    let (mut optimized, mut json) = (false, false);
+    let (mut at, mut count, mut only) = (0, 10, vec![]);
+    let mut files = vec![];
+    let mut i = 1;
+    while i < args.len() {
+        if let Some(cmd) = flag_command(&args[i]) {
+            if args.first().map(|x| x.as_str()) != Some(cmd) {
+                return Err(format!("{} only goes with {}\n{}", args[i], cmd, usage()));
+            }
+        }
+        match args[i].as_str() {
+            "--sound" => dialect = Dialect::Sound,
+            "--coprocessor" => dialect = Dialect::Coprocessor,
+            "--set" => {
+                i += 1;
+                registers.push(duet_vm::parse_assignment(args.get(i).map(|x| x.as_str()).unwrap_or(""))?);
+            },
+            "--send" => {
+                i += 1;
+                mailbox.push(number(args, i, "--send")?);
+            },
+            "--limit" => {
+                i += 1;
+                limit = Some(number(args, i, "--limit")?);
+            },
+            "--optimize" | "-O" => optimized = true,
+            "--json" => json = true,
+            "--at" => {
+                i += 1;
+                at = number(args, i, "--at")?;
+            },
+            "--count" | "-n" => {
+                i += 1;
+                count = number(args, i, "--count")?;
+            },
+            "--only" => {
+                i += 1;
+                only = parse_registers(args.get(i).map(|x| x.as_str()).unwrap_or(""))?;
+            },
+            "--help" | "-h" => return Err(usage()),
+            x if x.starts_with("--") => return Err(format!("Unexpected argument: {}\n{}", x, usage())),
+            x => files.push(x.to_string()),
+        }
+        i += 1;
+    }
+
+    let command = match (args.first().map(|x| x.as_str()), files.len()) {
+        (Some("record"), 2) => Command::Record(Recording {
+            dialect,
+            registers,
+            mailbox,
+            limit,
+            optimized,
+            json,
+            program: files.remove(0),
+            out: files.remove(0),
+        }),
+        (Some("show"), 1) => Command::Show { trace: files.remove(0), at, count },
+        (Some("diff"), 2) => Command::Diff { a: files.remove(0), b: files.remove(0), only },
+        _ => return Err(usage()),
+    };
+
+    Ok(command)
+}
+
+fn record(opts: &Recording) -> Result<(), String> {
+    let src = fs::read_to_string(&opts.program).map_err(|e| format!("{}: {}", opts.program, e))?;
+    let program = duet_vm::assemble(&src, opts.dialect).map_err(|e| duet_vm::asm::report(&src, &e))?;
+    let program = if opts.optimized { duet_vm::optimize::optimize(&program) } else { program };
+    let mut machine = Machine::new(&program, opts.dialect)?;
+    for &(r, v) in &opts.registers {
+        machine.set(r, v)?;
+    }
+    for v in &opts.mailbox {
+        machine.push(*v);
+    }
+    // written out as it goes, so a run that never halts
+    // can be stopped without losing what it's done
+    let write_err = |e: std::io::Error| format!("{}: {}", opts.out, e);
+    let file = File::create(&opts.out).map_err(write_err)?;
+    let mut writer = TraceWriter::new(file, opts.json, opts.dialect, &machine.registers()).map_err(write_err)?;
+    machine.record();
+    let mut steps = 0;
+    while opts.limit.is_none_or(|l| steps < l) {
+        match machine.step() {
+            Event::Halted | Event::Waiting | Event::Faulted => break,
+            _ => steps += 1,
+        }
+        if steps % 4096 == 0 {
+            for step in machine.drain_trace() {
+                writer.step(&step).map_err(write_err)?;
+            }
+            writer.flush().map_err(write_err)?;
+        }
+    }
+    for step in machine.drain_trace() {
+        writer.step(&step).map_err(write_err)?;
+    }
+    writer.flush().map_err(write_err)?;
+    let why = match machine.status() {
+        Status::Halted => "halted",
+        Status::Waiting => "waiting on rcv",
+        Status::Faulted => "faulted on mod by zero",
+        Status::Running => "stopped at the limit",
+    };
+    eprintln!("{} steps, {}", writer.len(), why);
+
+    Ok(())
+}
+
+fn load(path: &str) -> Result<Trace, String> {
+    let mut file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    Trace::read(&mut file).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn registers(regs: &[(char, i64)]) -> String {
+    let regs: Vec<String> = regs.iter().map(|&(r, v)| format!("{}={}", r, v)).collect();
+    regs.join(" ")
+}
+
+fn step_line(i: usize, step: &Step) -> String {
+    let mut notes = vec![];
+    match step.event {
+        Event::Played(v) => notes.push(format!("played {}", v)),
+        Event::Recovered(v) => notes.push(format!("recovered {}", v)),
+        Event::Sent(v) => notes.push(format!("sent {}", v)),
+        Event::Received(v) => notes.push(format!("received {}", v)),
+        _ => (),
+    }
+    notes.push(registers(&step.writes));
+
+    format!("{:>8}  {:>4}  {:<12}{}", i, step.pc, step.instr.to_string(), notes.join(" ")).trim_end().to_string()
+}
+
+fn show(trace: &Trace, at: usize, count: usize) {
+    println!("step {} of {}: {}", at, trace.len(), registers(&trace.registers_at(at)));
+    for (i, step) in trace.steps.iter().enumerate().skip(at).take(count) {
+        println!("{}", step_line(i, step));
+    }
+}
+
+// true when the traces agree
+fn diff(a: &Trace, b: &Trace, only: &[char]) -> bool {
+    let found = if only.is_empty() { trace::diff(a, b) } else { trace::diff_registers(a, b, only) };
+    match found {
+        None if only.is_empty() => println!("same {} steps", a.len()),
+        None => println!("same values for {}", only.iter().map(|r| r.to_string()).collect::<Vec<String>>().join(",")),
+        Some(Divergence::Step(i)) => {
+            println!("differ at step {}", i);
+            for (name, t) in [("a", a), ("b", b)].iter() {
+                println!("{}: {}", name, registers(&t.registers_at(i)));
+                if let Some(step) = t.steps.get(i) {
+                    println!("{}", step_line(i, step));
+                }
+            }
+        },
+        Some(Divergence::Length(n)) => println!("same first {} steps, then a has {} and b has {}", n, a.len(), b.len()),
+        Some(Divergence::History(r, i)) => {
+            let value = |t: &Trace| t.history(r).get(i).map(|v| v.to_string()).unwrap_or_else(|| "nothing".to_string());
+            println!("{} differs at its value #{}: a has {}, b has {}", r, i, value(a), value(b));
+        },
+    }
+
+    found.is_none()
+}
+
+fn run(command: Command) -> Result<bool, String> {
+    match command {
+        Command::Record(opts) => {
+            record(&opts)?;
+            Ok(true)
+        },
+        Command::Show { trace, at, count } => {
+            show(&load(&trace)?, at, count);
+            Ok(true)
+        },
+        Command::Diff { a, b, only } => Ok(diff(&load(&a)?, &load(&b)?, &only)),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match parse_args(&args).and_then(run) {
+        Ok(true) => (),
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        },
+    }
+}
+
+#[test]
+fn test() {
+    let args = |s: &str| -> Vec<String> { s.split(' ').map(|x| x.to_string()).collect() };
+    let rec = parse_args(&args("record --set a=1 --limit 100 prog.txt out.trace --json")).unwrap();
+    assert_eq!(Command::Record(Recording {
+        dialect: Dialect::Duet,
+        registers: vec![('a', 1)],
+        mailbox: vec![],
+        limit: Some(100),
+        optimized: false,
+        json: true,
+        program: "prog.txt".to_string(),
+        out: "out.trace".to_string(),
+    }), rec);
+    assert_eq!(Command::Show { trace: "t".to_string(), at: 5, count: 10 }, parse_args(&args("show t --at 5")).unwrap());
+    assert_eq!(Command::Diff { a: "x".to_string(), b: "y".to_string(), only: vec!['b', 'h'] }, parse_args(&args("diff x y --only b,h")).unwrap());
+    assert!(parse_args(&args("diff x y --only bh")).is_err());
+    assert!(parse_args(&args("record prog.txt")).is_err());
+    assert!(parse_args(&args("show t --at")).is_err());
+    assert!(parse_args(&args("replay t")).is_err());
+    assert!(parse_args(&args("show t --json")).unwrap_err().starts_with("--json only goes with record\n"));
+    assert!(parse_args(&args("diff x y --at 3")).is_err());
+    assert!(parse_args(&args("record --only b prog.txt out.trace")).is_err());
+    assert!(parse_args(&args("show t -n 3 --sound")).is_err());
+
+    let step = Step { pc: 3, instr: duet_vm::Instr::Rcv('b'), writes: vec![('b', 7)], event: Event::Received(7) };
+    assert_eq!("      12     3  rcv b       received 7 b=7", step_line(12, &step));
+}
@@ -0,0 +1,247 @@
+
+use std::fmt;
+use Instr;
+use Operand;
+use Program;
+
+// when a jump is taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cond {
+    NonZero(Operand),
+    Positive(Operand),
+    Zero(Operand),
+    NotPositive(Operand),
+}
+
+impl Cond {
+    pub fn negate(self) -> Cond {
+        match self {
+            Cond::NonZero(x) => Cond::Zero(x),
+            Cond::Zero(x) => Cond::NonZero(x),
+            Cond::Positive(x) => Cond::NotPositive(x),
+            Cond::NotPositive(x) => Cond::Positive(x),
+        }
+    }
+
+    pub fn operand(self) -> Operand {
+        match self {
+            Cond::NonZero(x) | Cond::Positive(x) | Cond::Zero(x) | Cond::NotPositive(x) => x,
+        }
+    }
+
+    pub fn comparison(self) -> &'static str {
+        match self {
+            Cond::NonZero(_) => "!= 0",
+            Cond::Positive(_) => "> 0",
+            Cond::Zero(_) => "== 0",
+            Cond::NotPositive(_) => "<= 0",
+        }
+    }
+}
+
+impl fmt::Display for Cond {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.operand(), self.comparison())
+    }
+}
+
+// What an instruction does to control flow. Jumps on a
+// constant are always or never taken; targets may fall
+// outside the program, which halts it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Branch {
+    Next,
+    Always(i64),
+    If(Cond, i64),
+    // an offset held in a register; the condition, if any
+    Computed(Option<Cond>, Operand),
+}
+
+pub fn branch(pc: usize, instr: &Instr) -> Branch {
+    let (cond, offset) = match *instr {
+        Instr::Jnz(x, y) => (Cond::NonZero(x), y),
+        Instr::Jgz(x, y) => (Cond::Positive(x), y),
+        _ => return Branch::Next,
+    };
+    let taken = match cond.operand() {
+        Operand::Value(v) => Some(if let Cond::NonZero(_) = cond { v != 0 } else { v > 0 }),
+        Operand::Register(_) => None,
+    };
+    match (taken, offset) {
+        (Some(false), _) => Branch::Next,
+        (Some(true), Operand::Value(o)) => Branch::Always(pc as i64 + o),
+        (None, Operand::Value(o)) => Branch::If(cond, pc as i64 + o),
+        (Some(true), x) => Branch::Computed(None, x),
+        (None, x) => Branch::Computed(Some(cond), x),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Block(usize),
+    // off either end of the program
+    Exit,
+    // wherever a computed jump lands
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Taken,
+    FallThrough,
+}
+
+// instructions start..end, entered only at start
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub succs: Vec<(Target, Edge)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cfg {
+    pub blocks: Vec<Block>,
+}
+
+impl Cfg {
+    pub fn new(program: &Program) -> Cfg {
+        let len = program.len();
+        let mut leader = vec![false; len + 1];
+        leader[0] = true;
+        for (pc, instr) in program.iter().enumerate() {
+            let target = match branch(pc, instr) {
+                Branch::Next => continue,
+                Branch::Always(t) | Branch::If(_, t) => Some(t),
+                Branch::Computed(_, _) => None,
+            };
+            leader[pc + 1] = true;
+            if let Some(t) = target.filter(|t| *t >= 0 && *t < len as i64) {
+                leader[t as usize] = true;
+            }
+        }
+
+        let starts: Vec<usize> = (0..len).filter(|pc| leader[*pc]).collect();
+        let mut blocks = vec![];
+        for (i, start) in starts.iter().enumerate() {
+            let end = starts.get(i + 1).cloned().unwrap_or(len);
+            blocks.push(Block { start: *start, end, succs: vec![] });
+        }
+        let block_of = |t: i64| match starts.binary_search(&(t as usize)) {
+            Ok(i) if t >= 0 && (t as usize) < len => Target::Block(i),
+            _ => Target::Exit,
+        };
+        for block in &mut blocks {
+            let last = block.end - 1;
+            let next = (block_of(block.end as i64), Edge::FallThrough);
+            block.succs = match branch(last, &program[last]) {
+                Branch::Next => vec![next],
+                Branch::Always(t) => vec![(block_of(t), Edge::Taken)],
+                Branch::If(_, t) => vec![(block_of(t), Edge::Taken), next],
+                Branch::Computed(None, _) => vec![(Target::Unknown, Edge::Taken)],
+                Branch::Computed(Some(_), _) => vec![(Target::Unknown, Edge::Taken), next],
+            };
+        }
+
+        Cfg { blocks }
+    }
+
+    pub fn block_at(&self, pc: usize) -> Option<usize> {
+        self.blocks.iter().position(|b| b.start <= pc && pc < b.end)
+    }
+
+    // (from, to) for each taken edge that goes back to an
+    // earlier or the same block, as loops do
+    pub fn back_edges(&self) -> Vec<(usize, usize)> {
+        let mut ret = vec![];
+        for (i, block) in self.blocks.iter().enumerate() {
+            for &(target, edge) in &block.succs {
+                match (target, edge) {
+                    (Target::Block(t), Edge::Taken) if t <= i => ret.push((i, t)),
+                    _ => (),
+                }
+            }
+        }
+
+        ret
+    }
+
+    // Graphviz, a box per block listing its instructions;
+    // jumps taken are solid and labelled with when they're
+    // taken, falling through is dashed
+    pub fn dot(&self, program: &Program) -> String {
+        let mut lines = vec!["digraph cfg {".to_string(), "    node [shape=box, fontname=monospace];".to_string()];
+        let (mut exits, mut unknown) = (false, false);
+        for block in &self.blocks {
+            let text: Vec<String> = (block.start..block.end).map(|pc| format!("{}: {}\\l", pc, program[pc])).collect();
+            lines.push(format!("    pc{} [label=\"{}\"];", block.start, text.concat()));
+            let last = block.end - 1;
+            let when = match branch(last, &program[last]) {
+                Branch::If(c, _) | Branch::Computed(Some(c), _) => format!(" [label=\"{}\"]", c),
+                _ => String::new(),
+            };
+            for &(target, edge) in &block.succs {
+                let to = match target {
+                    Target::Block(i) => format!("pc{}", self.blocks[i].start),
+                    Target::Exit => {
+                        exits = true;
+                        "exit".to_string()
+                    },
+                    Target::Unknown => {
+                        unknown = true;
+                        "unknown".to_string()
+                    },
+                };
+                let attrs = if edge == Edge::Taken { when.clone() } else { " [style=dashed]".to_string() };
+                lines.push(format!("    pc{} -> {}{};", block.start, to, attrs));
+            }
+        }
+        if exits {
+            lines.push("    exit [shape=oval, label=halt];".to_string());
+        }
+        if unknown {
+            lines.push("    unknown [shape=oval, label=\"?\"];".to_string());
+        }
+        lines.push("}".to_string());
+
+        lines.join("\n") + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assemble_any;
+    use cfg::Branch;
+    use cfg::Cfg;
+    use cfg::Cond;
+    use cfg::Edge;
+    use cfg::Target;
+    use Operand;
+
+    #[test]
+    fn it_works() {
+        let program = assemble_any("set a 3\nadd b a\nsub a 1\njnz a -2\njgz b 2\njnz 0 9\njgz 1 5\njgz a a\nsnd b").unwrap();
+        assert_eq!(Branch::If(Cond::NonZero(Operand::Register('a')), 1), ::cfg::branch(3, &program[3]));
+        assert_eq!(Branch::Next, ::cfg::branch(5, &program[5]));
+        assert_eq!(Branch::Always(11), ::cfg::branch(6, &program[6]));
+        assert_eq!("a <= 0", Cond::Positive(Operand::Register('a')).negate().to_string());
+
+        let cfg = Cfg::new(&program);
+        let spans: Vec<(usize, usize)> = cfg.blocks.iter().map(|b| (b.start, b.end)).collect();
+        assert_eq!(vec![(0, 1), (1, 4), (4, 5), (5, 6), (6, 7), (7, 8), (8, 9)], spans);
+        assert_eq!(vec![(Target::Block(1), Edge::Taken), (Target::Block(2), Edge::FallThrough)], cfg.blocks[1].succs);
+        assert_eq!(vec![(Target::Block(4), Edge::FallThrough)], cfg.blocks[3].succs);
+        assert_eq!(vec![(Target::Exit, Edge::Taken)], cfg.blocks[4].succs);
+        assert_eq!(vec![(Target::Unknown, Edge::Taken), (Target::Block(6), Edge::FallThrough)], cfg.blocks[5].succs);
+        assert_eq!(vec![(Target::Exit, Edge::FallThrough)], cfg.blocks[6].succs);
+        assert_eq!(vec![(1, 1)], cfg.back_edges());
+        assert_eq!(Some(4), cfg.block_at(6));
+
+        let dot = cfg.dot(&program);
+        assert!(dot.contains("    pc1 [label=\"1: add b a\\l2: sub a 1\\l3: jnz a -2\\l\"];\n"));
+        assert!(dot.contains("    pc1 -> pc1 [label=\"a != 0\"];\n    pc1 -> pc4 [style=dashed];\n"));
+        assert!(dot.contains("    pc7 -> unknown [label=\"a > 0\"];\n"));
+        assert!(dot.contains("    pc6 -> exit;\n"));
+        assert!(dot.ends_with("    exit [shape=oval, label=halt];\n    unknown [shape=oval, label=\"?\"];\n}\n"));
+    }
+}
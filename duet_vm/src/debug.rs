@@ -0,0 +1,480 @@
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::io::Write;
+use Event;
+use Machine;
+use Operand;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Cmp {
+    pub fn from_name(s: &str) -> Option<Cmp> {
+        match s {
+            "==" => Some(Cmp::Eq),
+            "!=" => Some(Cmp::Ne),
+            "<" => Some(Cmp::Lt),
+            "<=" => Some(Cmp::Le),
+            ">" => Some(Cmp::Gt),
+            ">=" => Some(Cmp::Ge),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Cmp::Eq => "==",
+            Cmp::Ne => "!=",
+            Cmp::Lt => "<",
+            Cmp::Le => "<=",
+            Cmp::Gt => ">",
+            Cmp::Ge => ">=",
+        }
+    }
+
+    pub fn test(&self, a: i64, b: i64) -> bool {
+        match *self {
+            Cmp::Eq => a == b,
+            Cmp::Ne => a != b,
+            Cmp::Lt => a < b,
+            Cmp::Le => a <= b,
+            Cmp::Gt => a > b,
+            Cmp::Ge => a >= b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    // stop before the instruction at pc runs
+    Pc(i64),
+    // stop once the comparison turns true
+    Reg(char, Cmp, Operand),
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Breakpoint::Pc(pc) => write!(f, "pc {}", pc),
+            Breakpoint::Reg(r, cmp, x) => write!(f, "{} {} {}", r, cmp.name(), x),
+        }
+    }
+}
+
+// why the machine stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    // ran the requested number of steps
+    Steps,
+    Breakpoint(usize),
+    // register, old value, new value
+    Watch(char, i64, i64),
+    Waiting,
+    Halted,
+    // mod by zero; pc stays on it
+    Faulted,
+}
+
+pub struct Debugger {
+    machine: Machine,
+    breakpoints: BTreeMap<usize, Breakpoint>,
+    next_id: usize,
+    watches: Vec<char>,
+    steps: u64,
+}
+
+fn parse_register(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_lowercase() => Ok(c),
+        _ => Err(format!("`{}` isn't a register", s)),
+    }
+}
+
+fn parse_value(s: &str) -> Result<i64, String> {
+    s.parse().map_err(|_e| format!("`{}` isn't a number", s))
+}
+
+fn parse_operand(s: &str) -> Result<Operand, String> {
+    match s.parse() {
+        Ok(v) => Ok(Operand::Value(v)),
+        Err(_e) => parse_register(s).map(Operand::Register),
+    }
+}
+
+// "12" or "a > 5"
+pub fn parse_breakpoint(args: &[&str]) -> Result<Breakpoint, String> {
+    match args.len() {
+        1 => parse_value(args[0]).map(Breakpoint::Pc),
+        3 => {
+            let cmp = Cmp::from_name(args[1]).ok_or_else(|| format!("`{}` isn't a comparison", args[1]))?;
+            Ok(Breakpoint::Reg(parse_register(args[0])?, cmp, parse_operand(args[2])?))
+        },
+        _ => Err("break takes a pc or a condition like `a > 5`".to_string()),
+    }
+}
+
+fn describe(event: Event) -> Option<String> {
+    match event {
+        Event::Played(v) => Some(format!("played {}", v)),
+        Event::Recovered(v) => Some(format!("recovered {}", v)),
+        Event::Sent(v) => Some(format!("sent {}", v)),
+        Event::Received(v) => Some(format!("received {}", v)),
+        _ => None,
+    }
+}
+
+pub const HELP: &str = "\
+step [N]        s  run N instructions (default 1)
+continue        c  run until a breakpoint, watch, halt, fault or empty mailbox
+break PC        b  stop before the instruction at PC
+break R CMP X      stop when R CMP X turns true (== != < <= > >=)
+delete ID       d  remove a breakpoint
+watch R         w  stop whenever R changes
+unwatch R          stop watching R
+info            i  list breakpoints and watches
+regs            r  show registers
+set R V            poke a register
+send V             queue a value for rcv
+list [N]        l  disassemble N lines either side of pc (default 5)
+help            h  this
+quit            q  leave";
+
+impl Debugger {
+    pub fn new(machine: Machine) -> Debugger {
+        Debugger { machine, breakpoints: BTreeMap::new(), next_id: 1, watches: vec![], steps: 0 }
+    }
+
+    pub fn machine(&self) -> &Machine {
+        &self.machine
+    }
+
+    pub fn machine_mut(&mut self) -> &mut Machine {
+        &mut self.machine
+    }
+
+    // instructions run under the debugger so far
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn add_breakpoint(&mut self, b: Breakpoint) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.breakpoints.insert(id, b);
+        id
+    }
+
+    pub fn remove_breakpoint(&mut self, id: usize) -> bool {
+        self.breakpoints.remove(&id).is_some()
+    }
+
+    pub fn breakpoints(&self) -> &BTreeMap<usize, Breakpoint> {
+        &self.breakpoints
+    }
+
+    pub fn watch(&mut self, r: char) {
+        if !self.watches.contains(&r) {
+            self.watches.push(r);
+        }
+    }
+
+    pub fn unwatch(&mut self, r: char) -> bool {
+        let before = self.watches.len();
+        self.watches.retain(|x| *x != r);
+        before != self.watches.len()
+    }
+
+    pub fn watches(&self) -> &[char] {
+        &self.watches
+    }
+
+    fn holds(&self, b: &Breakpoint) -> bool {
+        match *b {
+            Breakpoint::Pc(pc) => self.machine.pc() == pc,
+            Breakpoint::Reg(r, cmp, x) => cmp.test(self.machine.get(r), self.machine.value(x)),
+        }
+    }
+
+    // one instruction; anything it sent or received goes to log
+    fn step_once(&mut self, log: &mut Vec<String>) -> Option<Stop> {
+        let watched: Vec<i64> = self.watches.iter().map(|r| self.machine.get(*r)).collect();
+        let held: Vec<bool> = self.breakpoints.values().map(|b| self.holds(b)).collect();
+        let event = self.machine.step();
+        match event {
+            Event::Halted => return Some(Stop::Halted),
+            Event::Waiting => return Some(Stop::Waiting),
+            Event::Faulted => return Some(Stop::Faulted),
+            _ => self.steps += 1,
+        }
+        if let Some(s) = describe(event) {
+            log.push(s);
+        }
+
+        for (r, old) in self.watches.iter().zip(watched) {
+            let new = self.machine.get(*r);
+            if new != old {
+                return Some(Stop::Watch(*r, old, new));
+            }
+        }
+        for ((id, b), before) in self.breakpoints.iter().zip(held) {
+            let armed = match *b {
+                Breakpoint::Pc(_) => true,
+                Breakpoint::Reg(..) => !before,
+            };
+            if armed && self.holds(b) {
+                return Some(Stop::Breakpoint(*id));
+            }
+        }
+
+        None
+    }
+
+    // stops early for breakpoints and watches
+    pub fn step(&mut self, n: usize, log: &mut Vec<String>) -> Stop {
+        for _i in 0..n {
+            if let Some(stop) = self.step_once(log) {
+                return stop;
+            }
+        }
+
+        Stop::Steps
+    }
+
+    pub fn cont(&mut self, log: &mut Vec<String>) -> Stop {
+        loop {
+            if let Some(stop) = self.step_once(log) {
+                return stop;
+            }
+        }
+    }
+
+    // pc marked with =>, pc breakpoints with *
+    pub fn disassemble(&self, radius: usize) -> Vec<String> {
+        let program = self.machine.program();
+        let pc = self.machine.pc();
+        let first = (pc - radius as i64).max(0);
+        let last = (pc + radius as i64).min(program.len() as i64 - 1);
+        (first..=last).map(|i| {
+            let here = if i == pc { "=>" } else { "  " };
+            let bp = if self.breakpoints.values().any(|b| *b == Breakpoint::Pc(i)) { '*' } else { ' ' };
+            format!("{}{} {:>4}  {}", here, bp, i, program[i as usize])
+        }).collect()
+    }
+
+    fn location(&self) -> String {
+        let pc = self.machine.pc();
+        match self.machine.program().get(pc as usize) {
+            Some(instr) if pc >= 0 => format!("{:>4}  {}", pc, instr),
+            _ => format!("pc {} is outside the program", pc),
+        }
+    }
+
+    fn report(&self, stop: Stop, log: &[String], out: &mut dyn Write) -> io::Result<()> {
+        for line in log {
+            writeln!(out, "{}", line)?;
+        }
+        match stop {
+            Stop::Steps => (),
+            Stop::Breakpoint(id) => writeln!(out, "breakpoint {}: {}", id, self.breakpoints[&id])?,
+            Stop::Watch(r, old, new) => writeln!(out, "watch {}: {} -> {}", r, old, new)?,
+            Stop::Waiting => writeln!(out, "waiting on rcv with an empty mailbox; `send` a value")?,
+            Stop::Halted => writeln!(out, "halted after {} steps", self.steps)?,
+            Stop::Faulted => writeln!(out, "mod by zero; `set` the divisor to go on")?,
+        }
+        writeln!(out, "{}", self.location())
+    }
+
+    fn command(&mut self, cmd: &str, args: &[&str], out: &mut dyn Write) -> Result<io::Result<()>, String> {
+        let arity = |n: usize| if args.len() > n { Err(format!("{} takes at most {} argument(s)", cmd, n)) } else { Ok(()) };
+        let mut log = vec![];
+        let written = match cmd {
+            "step" | "s" => {
+                arity(1)?;
+                let n = match args.first() {
+                    Some(n) => parse_value(n)?.max(0) as usize,
+                    None => 1,
+                };
+                let stop = self.step(n, &mut log);
+                self.report(stop, &log, out)
+            },
+            "continue" | "c" => {
+                arity(0)?;
+                let stop = self.cont(&mut log);
+                self.report(stop, &log, out)
+            },
+            "break" | "b" => {
+                let b = parse_breakpoint(args)?;
+                let id = self.add_breakpoint(b);
+                writeln!(out, "breakpoint {}: {}", id, b)
+            },
+            "delete" | "d" => {
+                arity(1)?;
+                let id = parse_value(args.first().unwrap_or(&""))? as usize;
+                if !self.remove_breakpoint(id) {
+                    return Err(format!("no breakpoint {}", id));
+                }
+                Ok(())
+            },
+            "watch" | "w" | "unwatch" => {
+                if args.len() != 1 {
+                    return Err(format!("{} takes a register", cmd));
+                }
+                let r = parse_register(args[0])?;
+                if cmd == "unwatch" {
+                    if !self.unwatch(r) {
+                        return Err(format!("{} isn't watched", r));
+                    }
+                    Ok(())
+                } else {
+                    self.watch(r);
+                    writeln!(out, "watching {} = {}", r, self.machine.get(r))
+                }
+            },
+            "info" | "i" => {
+                arity(0)?;
+                let mut ret = Ok(());
+                for (id, b) in &self.breakpoints {
+                    ret = ret.and(writeln!(out, "breakpoint {}: {}", id, b));
+                }
+                for r in &self.watches {
+                    ret = ret.and(writeln!(out, "watch {} = {}", r, self.machine.get(*r)));
+                }
+                ret
+            },
+            "regs" | "r" => {
+                arity(0)?;
+                let regs: Vec<String> = self.machine.registers().iter().map(|(r, v)| format!("{}={}", r, v)).collect();
+                writeln!(out, "pc={} {}", self.machine.pc(), regs.join(" "))
+            },
+            "set" => {
+                if args.len() != 2 {
+                    return Err("set takes a register and a value".to_string());
+                }
+                let (r, v) = (parse_register(args[0])?, parse_value(args[1])?);
+                self.machine.set(r, v)?;
+                Ok(())
+            },
+            "send" => {
+                if args.len() != 1 {
+                    return Err("send takes a value".to_string());
+                }
+                let v = parse_value(args[0])?;
+                self.machine.push(v);
+                Ok(())
+            },
+            "list" | "l" => {
+                arity(1)?;
+                let radius = match args.first() {
+                    Some(n) => parse_value(n)?.max(0) as usize,
+                    None => 5,
+                };
+                let lines = self.disassemble(radius);
+                writeln!(out, "{}", lines.join("\n"))
+            },
+            "help" | "h" => writeln!(out, "{}", HELP),
+            _ => return Err(format!("unknown command `{}`; try help", cmd)),
+        };
+
+        Ok(written)
+    }
+
+    // Runs one command line, writing the results to out.
+    // False once the user asks to quit.
+    pub fn exec(&mut self, line: &str, out: &mut dyn Write) -> io::Result<bool> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (cmd, args) = match words.split_first() {
+            Some((cmd, args)) => (*cmd, args),
+            None => return Ok(true),
+        };
+        if cmd == "quit" || cmd == "q" {
+            return Ok(false);
+        }
+        match self.command(cmd, args, out) {
+            Ok(written) => written?,
+            Err(e) => writeln!(out, "error: {}", e)?,
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assemble;
+    use assemble_any;
+    use debug::Breakpoint;
+    use debug::Cmp;
+    use debug::Debugger;
+    use debug::Stop;
+    use Dialect;
+    use Machine;
+    use Operand;
+
+    #[test]
+    fn it_works() {
+        // counts a down from 3, sending each value
+        let program = assemble_any("set a 3\nsnd a\nsub a 1\njgz a -2\nrcv b\nadd b 1").unwrap();
+        let mut dbg = Debugger::new(Machine::new(&program, Dialect::Duet).unwrap());
+        let mut log = vec![];
+        assert_eq!(Stop::Steps, dbg.step(2, &mut log));
+        assert_eq!(vec!["sent 3"], log);
+
+        let bp = dbg.add_breakpoint(Breakpoint::Reg('a', Cmp::Eq, Operand::Value(1)));
+        assert_eq!(Stop::Breakpoint(bp), dbg.cont(&mut log));
+        assert_eq!((3, 1), (dbg.machine().pc(), dbg.machine().get('a')));
+        assert!(dbg.remove_breakpoint(bp));
+
+        let at = dbg.add_breakpoint(Breakpoint::Pc(4));
+        assert_eq!(Stop::Breakpoint(at), dbg.cont(&mut log));
+        assert_eq!(vec!["sent 3", "sent 2", "sent 1"], log);
+        assert_eq!(Stop::Waiting, dbg.cont(&mut log));
+
+        dbg.watch('b');
+        dbg.machine_mut().push(41);
+        assert_eq!(Stop::Watch('b', 0, 41), dbg.cont(&mut log));
+        assert_eq!(Stop::Watch('b', 41, 42), dbg.cont(&mut log));
+        assert_eq!(Stop::Halted, dbg.cont(&mut log));
+        assert_eq!(12, dbg.steps());
+
+        let mut poked = Debugger::new(Machine::new(&program, Dialect::Duet).unwrap());
+        let mut out = vec![];
+        let script = ["b 2", "c", "set a 1", "regs", "l 1", "nope", "b a ~ 2", "c", "q"];
+        let mut going = vec![];
+        for line in script.iter() {
+            going.push(poked.exec(line, &mut out).unwrap());
+        }
+        assert_eq!(vec![true, true, true, true, true, true, true, true, false], going);
+        let expected = [
+            "breakpoint 1: pc 2",
+            "sent 3",
+            "breakpoint 1: pc 2",
+            "   2  sub a 1",
+            "pc=2 a=1",
+            "       1  snd a",
+            "=>*    2  sub a 1",
+            "       3  jgz a -2",
+            "error: unknown command `nope`; try help",
+            "error: `~` isn't a comparison",
+            "waiting on rcv with an empty mailbox; `send` a value",
+            "   4  rcv b",
+        ];
+        assert_eq!(expected.join("\n") + "\n", String::from_utf8(out).unwrap());
+
+        let mut divide = Debugger::new(Machine::new(&assemble("mod a b\nadd a 1", Dialect::Duet).unwrap(), Dialect::Duet).unwrap());
+        assert_eq!(Stop::Faulted, divide.cont(&mut log));
+        assert_eq!((0, 0), (divide.machine().pc(), divide.steps()));
+        divide.machine_mut().set('b', 2).unwrap();
+        assert_eq!(Stop::Halted, divide.cont(&mut log));
+        assert_eq!(1, divide.machine().get('a'));
+    }
+}
@@ -0,0 +1,134 @@
+
+use Instr;
+use Operand;
+use Program;
+
+// registers a through z
+pub const REGISTERS: usize = 26;
+
+// r's slot in the register file
+pub fn index(r: char) -> Option<usize> {
+    if r.is_ascii_lowercase() { Some((r as u8 - b'a') as usize) } else { None }
+}
+
+// the same, for a register that has to be one
+pub fn slot(r: char) -> Result<usize, String> {
+    index(r).ok_or_else(|| format!("`{}` isn't a register (a through z)", r))
+}
+
+// an operand with its register already looked up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Src {
+    Reg(usize),
+    Imm(i64),
+}
+
+impl Src {
+    fn of(x: Operand) -> Result<Src, String> {
+        match x {
+            Operand::Register(r) => Ok(Src::Reg(slot(r)?)),
+            Operand::Value(v) => Ok(Src::Imm(v)),
+        }
+    }
+
+    #[inline]
+    pub fn get(self, regs: &[i64; REGISTERS]) -> i64 {
+        match self {
+            Src::Reg(i) => regs[i],
+            Src::Imm(v) => v,
+        }
+    }
+}
+
+// An instruction as the machine runs it. Jumps whose
+// condition is a constant become Goto or Nop, as they're
+// always or never taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Code {
+    Snd(Src),
+    Rcv(usize),
+    Set(usize, Src),
+    Add(usize, Src),
+    Sub(usize, Src),
+    Mul(usize, Src),
+    Mod(usize, Src),
+    Jgz(Src, Src),
+    Jnz(Src, Src),
+    Goto(Src),
+    Nop,
+    Mac(usize, Src, usize),
+    Dvt(usize, Src, usize, Src),
+    Fct(usize, usize, usize, Src),
+}
+
+// Err for an Instr built by hand with something
+// other than a through z for a register
+pub fn decode(instr: Instr) -> Result<Code, String> {
+    let constant = |x: Operand, taken: fn(i64) -> bool, y: Operand, otherwise: Code| match x {
+        Operand::Value(v) if taken(v) => Ok(Code::Goto(Src::of(y)?)),
+        Operand::Value(_) => Ok(Code::Nop),
+        Operand::Register(_) => Ok(otherwise),
+    };
+    match instr {
+        Instr::Snd(x) => Ok(Code::Snd(Src::of(x)?)),
+        Instr::Rcv(r) => Ok(Code::Rcv(slot(r)?)),
+        Instr::Set(r, x) => Ok(Code::Set(slot(r)?, Src::of(x)?)),
+        Instr::Add(r, x) => Ok(Code::Add(slot(r)?, Src::of(x)?)),
+        Instr::Sub(r, x) => Ok(Code::Sub(slot(r)?, Src::of(x)?)),
+        Instr::Mul(r, x) => Ok(Code::Mul(slot(r)?, Src::of(x)?)),
+        Instr::Mod(r, x) => Ok(Code::Mod(slot(r)?, Src::of(x)?)),
+        Instr::Jgz(x, y) => constant(x, |v| v > 0, y, Code::Jgz(Src::of(x)?, Src::of(y)?)),
+        Instr::Jnz(x, y) => constant(x, |v| v != 0, y, Code::Jnz(Src::of(x)?, Src::of(y)?)),
+        Instr::Mac(r, x, c) => Ok(Code::Mac(slot(r)?, Src::of(x)?, slot(c)?)),
+        Instr::Dvt(f, d, e, n) => Ok(Code::Dvt(slot(f)?, Src::of(d)?, slot(e)?, Src::of(n)?)),
+        Instr::Fct(f, d, e, n) => Ok(Code::Fct(slot(f)?, slot(d)?, slot(e)?, Src::of(n)?)),
+    }
+}
+
+// A program decoded once, for however many machines run it.
+// The opcodes are kept alongside for counting executions.
+#[derive(Debug)]
+pub struct Image {
+    pub program: Program,
+    pub code: Vec<Code>,
+    pub ops: Vec<usize>,
+}
+
+impl Image {
+    pub fn new(program: &Program) -> Result<Image, String> {
+        let code = program.iter().enumerate().map(|(pc, x)| decode(*x).map_err(|e| format!("pc {}: {}", pc, e))).collect::<Result<_, _>>()?;
+
+        Ok(Image {
+            program: program.clone(),
+            code,
+            ops: program.iter().map(|x| x.op() as usize).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assemble_any;
+    use decode::Code;
+    use decode::Image;
+    use decode::Src;
+    use Instr;
+    use Operand;
+
+    #[test]
+    fn it_works() {
+        let image = Image::new(&assemble_any("set b 3\njgz a b\njnz 1 -1\njgz 0 4\nmac h 2 z").unwrap()).unwrap();
+        assert_eq!(vec![
+            Code::Set(1, Src::Imm(3)),
+            Code::Jgz(Src::Reg(0), Src::Reg(1)),
+            Code::Goto(Src::Imm(-1)),
+            Code::Nop,
+            Code::Mac(7, Src::Imm(2), 25),
+        ], image.code);
+        assert_eq!(vec![2, 7, 8, 7, 9], image.ops);
+
+        // by hand, not through the assembler
+        let bad = vec![Instr::Set('a', Operand::Value(1)), Instr::Jgz(Operand::Value(1), Operand::Register('A'))];
+        assert_eq!(Some("pc 1: `A` isn't a register (a through z)".to_string()), Image::new(&bad).err());
+    }
+}
@@ -0,0 +1,458 @@
+
+use std::collections::BTreeSet;
+use cfg;
+use cfg::Branch;
+use cfg::Cond;
+use Dialect;
+use Instr;
+use Op;
+use Operand;
+use Program;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    Instr(Instr),
+    // condition, then, else
+    If(Cond, Vec<Stmt>, Vec<Stmt>),
+    // do { } while the condition holds, or forever
+    Loop(Option<Cond>, Vec<Stmt>),
+    // out of, or on to the next turn of, the loop at a pc
+    Break(usize),
+    Continue(usize),
+    Halt,
+    Goto(usize),
+    // a jump by a register's value, when the condition holds
+    Jump(Option<Cond>, Operand),
+}
+
+// a statement, and the pc it starts at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stmt {
+    pub pc: usize,
+    pub kind: Kind,
+}
+
+struct Loop {
+    head: usize,
+    // where a jump means continue: the head, or the condition
+    next: usize,
+    exit: usize,
+}
+
+struct Decompiler<'a> {
+    program: &'a Program,
+    branches: Vec<Branch>,
+}
+
+impl<'a> Decompiler<'a> {
+    fn target(&self, pc: usize) -> Option<i64> {
+        match self.branches[pc] {
+            Branch::Always(t) | Branch::If(_, t) => Some(t),
+            _ => None,
+        }
+    }
+
+    // whether anything outside lo..hi jumps past lo into it
+    fn entered(&self, lo: usize, hi: usize) -> bool {
+        (0..self.program.len()).filter(|pc| *pc < lo || *pc >= hi).any(|pc| match self.target(pc) {
+            Some(t) => t > lo as i64 && t < hi as i64,
+            None => false,
+        })
+    }
+
+    fn jump(&self, t: i64, loops: &[Loop]) -> Kind {
+        if t < 0 || t >= self.program.len() as i64 {
+            return Kind::Halt;
+        }
+        for l in loops.iter().rev() {
+            if t == l.exit as i64 {
+                return Kind::Break(l.head);
+            } else if t == l.next as i64 {
+                return Kind::Continue(l.head);
+            }
+        }
+
+        Kind::Goto(t as usize)
+    }
+
+    // the outermost loop starting at pc and ending before hi,
+    // as the pc of the jump back
+    fn loop_at(&self, pc: usize, hi: usize) -> Option<usize> {
+        (pc..hi).rev().find(|j| self.target(*j) == Some(pc as i64) && !self.entered(pc, j + 1))
+    }
+
+    fn block(&self, lo: usize, hi: usize, loops: &mut Vec<Loop>) -> Vec<Stmt> {
+        let mut out = vec![];
+        let mut pc = lo;
+        while pc < hi {
+            if let Some(j) = self.loop_at(pc, hi) {
+                let cond = match self.branches[j] {
+                    Branch::If(c, _) => Some(c),
+                    _ => None,
+                };
+                loops.push(Loop { head: pc, next: if cond.is_some() { j } else { pc }, exit: j + 1 });
+                let body = self.block(pc, j, loops);
+                loops.pop();
+                out.push(Stmt { pc, kind: Kind::Loop(cond, body) });
+                pc = j + 1;
+                continue;
+            }
+
+            let kind = match self.branches[pc] {
+                Branch::Next => match self.program[pc] {
+                    // never taken
+                    Instr::Jgz(_, _) | Instr::Jnz(_, _) => None,
+                    instr => Some(Kind::Instr(instr)),
+                },
+                Branch::Always(t) => Some(self.jump(t, loops)),
+                Branch::Computed(c, x) => Some(Kind::Jump(c, x)),
+                Branch::If(c, t) if t > pc as i64 && t <= hi as i64 && !self.entered(pc + 1, t as usize) => {
+                    let t = t as usize;
+                    // a jump over the rest of the then block makes an else
+                    let (then_end, else_end) = match self.branches[t - 1] {
+                        Branch::Always(u) if t - 1 > pc && u > t as i64 && u <= hi as i64 && !self.entered(t, u as usize) => (t - 1, u as usize),
+                        _ => (t, t),
+                    };
+                    let then = self.block(pc + 1, then_end, loops);
+                    let other = self.block(t, else_end, loops);
+                    let start = pc;
+                    pc = else_end;
+                    // the jump skips then, so then runs when c doesn't hold
+                    let kind = match (then.is_empty(), other.is_empty()) {
+                        (true, true) => None,
+                        (true, false) => Some(Kind::If(c, other, vec![])),
+                        _ => Some(Kind::If(c.negate(), then, other)),
+                    };
+                    if let Some(k) = kind {
+                        out.push(Stmt { pc: start, kind: k });
+                    }
+                    continue;
+                },
+                Branch::If(c, t) => Some(Kind::If(c, vec![Stmt { pc, kind: self.jump(t, loops) }], vec![])),
+            };
+            if let Some(k) = kind {
+                out.push(Stmt { pc, kind: k });
+            }
+            pc += 1;
+        }
+
+        out
+    }
+}
+
+// Recovers loops from the jumps back and ifs from those
+// forward. Anything that doesn't nest is left as a goto.
+pub fn structure(program: &Program) -> Vec<Stmt> {
+    let branches = program.iter().enumerate().map(|(pc, x)| cfg::branch(pc, x)).collect();
+    let d = Decompiler { program, branches };
+
+    d.block(0, program.len(), &mut vec![])
+}
+
+struct Printer {
+    rust: bool,
+    dialect: Dialect,
+    // gotos' targets, and loops broken or continued from inside another
+    labels: BTreeSet<usize>,
+    // those already printed; a loop and its first statement share a pc
+    placed: BTreeSet<usize>,
+    // heads of the loops we're inside, and whether each is a do-while
+    loops: Vec<(usize, bool)>,
+    lines: Vec<String>,
+    depth: usize,
+}
+
+fn visit(stmts: &[Stmt], loops: &mut Vec<usize>, labels: &mut BTreeSet<usize>) {
+    for s in stmts {
+        match s.kind {
+            Kind::If(_, ref a, ref b) => {
+                visit(a, loops, labels);
+                visit(b, loops, labels);
+            },
+            Kind::Loop(_, ref body) => {
+                loops.push(s.pc);
+                visit(body, loops, labels);
+                loops.pop();
+            },
+            Kind::Break(head) | Kind::Continue(head) if loops.last() != Some(&head) => {
+                labels.insert(head);
+            },
+            Kind::Goto(t) => {
+                labels.insert(t);
+            },
+            _ => (),
+        }
+    }
+}
+
+fn registers(stmts: &[Stmt], regs: &mut BTreeSet<char>) {
+    for s in stmts {
+        let (instr, cond, nested): (Option<Instr>, Option<Cond>, Vec<&Vec<Stmt>>) = match s.kind {
+            Kind::Instr(i) => (Some(i), None, vec![]),
+            Kind::If(c, ref a, ref b) => (None, Some(c), vec![a, b]),
+            Kind::Loop(c, ref body) => (None, c, vec![body]),
+            Kind::Jump(c, x) => (Some(Instr::Snd(x)), c, vec![]),
+            _ => (None, None, vec![]),
+        };
+        let mut operands = instr.map(|i| i.operands()).unwrap_or_default();
+        operands.extend(cond.map(|c| c.operand()));
+        for x in operands {
+            if let Operand::Register(r) = x {
+                regs.insert(r);
+            }
+        }
+        for n in nested {
+            registers(n, regs);
+        }
+    }
+}
+
+impl Printer {
+    fn line(&mut self, s: String) {
+        self.lines.push(format!("{}{}", "    ".repeat(self.depth), s));
+    }
+
+    fn reg(&self, r: char) -> String {
+        if self.rust { format!("r.{}", r) } else { r.to_string() }
+    }
+
+    fn operand(&self, x: Operand) -> String {
+        match x {
+            Operand::Register(r) => self.reg(r),
+            Operand::Value(v) => v.to_string(),
+        }
+    }
+
+    fn cond(&self, c: Cond) -> String {
+        format!("{} {}", self.operand(c.operand()), c.comparison())
+    }
+
+    fn label(&self, head: usize) -> String {
+        if self.rust { format!("'l{}", head) } else { format!("L{}", head) }
+    }
+
+    // one statement per line, less the semicolons
+    fn instr(&self, instr: Instr) -> Vec<String> {
+        let semi = if self.rust { ";" } else { "" };
+        let op = |r: char, sym: &str, x: Operand| format!("{} {} {}", self.reg(r), sym, self.operand(x));
+        // adding -5 reads better as subtracting 5
+        let negated = |x: Operand| match x {
+            Operand::Value(v) if v < 0 => v.checked_neg().map(Operand::Value),
+            _ => None,
+        };
+        let ret = match instr {
+            Instr::Set(r, x) => vec![op(r, "=", x)],
+            Instr::Add(r, x) => vec![negated(x).map(|n| op(r, "-=", n)).unwrap_or_else(|| op(r, "+=", x))],
+            Instr::Sub(r, x) => vec![negated(x).map(|n| op(r, "+=", n)).unwrap_or_else(|| op(r, "-=", x))],
+            Instr::Mul(r, x) => vec![op(r, "*=", x)],
+            Instr::Mod(r, x) => vec![op(r, "%=", x)],
+            Instr::Snd(x) => match (self.rust, self.dialect) {
+                (true, _) => vec![format!("snd({})", self.operand(x))],
+                (false, Dialect::Sound) => vec![format!("play({})", self.operand(x))],
+                (false, Dialect::Duet) | (false, Dialect::Coprocessor) => vec![format!("send({})", self.operand(x))],
+            },
+            Instr::Rcv(r) => match (self.rust, self.dialect) {
+                (true, Dialect::Sound) => vec![format!("if {} != 0 && rcv() {{ return; }}", self.reg(r))],
+                (true, Dialect::Duet) | (true, Dialect::Coprocessor) => vec![format!("{} = match rcv() {{ Some(v) => v, None => return }}", self.reg(r))],
+                (false, Dialect::Sound) => vec![format!("if {} != 0 {{ recover() }}", self.reg(r))],
+                (false, Dialect::Duet) | (false, Dialect::Coprocessor) => vec![format!("{} = receive()", self.reg(r))],
+            },
+            Instr::Mac(r, x, c) => vec![format!("{} += {} * {}", self.reg(r), self.operand(x), self.reg(c)), format!("{} = 0", self.reg(c))],
+            Instr::Dvt(f, d, e, n) => vec![
+                format!("if ({}..{}).any(|k| {} * k == {}) {{ {} = 0{} }}", self.reg(e), self.operand(n), self.operand(d), self.operand(n), self.reg(f), semi),
+                op(e, "=", n),
+            ],
+            Instr::Fct(f, d, e, n) => {
+                let n_ = self.operand(n);
+                vec![
+                    format!("if ({}..{}).any(|j| ({}..{}).any(|k| j * k == {})) {{ {} = 0{} }}", self.reg(d), n_, self.reg(e), n_, n_, self.reg(f), semi),
+                    op(d, "=", n),
+                    op(e, "=", n),
+                ]
+            },
+            Instr::Jgz(_, _) | Instr::Jnz(_, _) => vec![],
+        };
+
+        ret.into_iter().map(|s| if s.ends_with('}') || !self.rust { s } else { s + semi }).collect()
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) -> Result<(), String> {
+        for s in stmts {
+            let is_loop = matches!(s.kind, Kind::Loop(_, _));
+            // loops carry their labels on the same line
+            if self.labels.contains(&s.pc) && !self.rust && !is_loop && self.placed.insert(s.pc) {
+                self.lines.push(format!("{}:", self.label(s.pc)));
+            }
+            self.stmt(s)?;
+        }
+
+        Ok(())
+    }
+
+    fn stmt(&mut self, s: &Stmt) -> Result<(), String> {
+        let semi = if self.rust { ";" } else { "" };
+        let unsupported = |what: &str| Err(format!("{} at pc {} has no Rust equivalent", what, s.pc));
+        match s.kind {
+            Kind::Instr(i) => {
+                for l in self.instr(i) {
+                    self.line(l);
+                }
+            },
+            Kind::If(c, ref then, ref other) => {
+                let c = self.cond(c);
+                self.line(format!("if {} {{", c));
+                self.depth += 1;
+                self.stmts(then)?;
+                self.depth -= 1;
+                if !other.is_empty() {
+                    self.line("} else {".to_string());
+                    self.depth += 1;
+                    self.stmts(other)?;
+                    self.depth -= 1;
+                }
+                self.line("}".to_string());
+            },
+            Kind::Loop(c, ref body) => {
+                let label = if self.labels.contains(&s.pc) && self.placed.insert(s.pc) { format!("{}: ", self.label(s.pc)) } else { String::new() };
+                let opener = if c.is_some() && !self.rust { "do" } else { "loop" };
+                self.line(format!("{}{} {{", label, opener));
+                self.depth += 1;
+                self.loops.push((s.pc, c.is_some()));
+                self.stmts(body)?;
+                self.loops.pop();
+                match c {
+                    Some(c) if self.rust => {
+                        let until = self.cond(c.negate());
+                        self.line(format!("if {} {{ break; }}", until));
+                    },
+                    _ => (),
+                }
+                self.depth -= 1;
+                match c {
+                    Some(c) if !self.rust => {
+                        let c = self.cond(c);
+                        self.line(format!("}} while {}", c));
+                    },
+                    _ => self.line("}".to_string()),
+                }
+            },
+            Kind::Break(head) | Kind::Continue(head) => {
+                let word = if let Kind::Break(_) = s.kind { "break" } else { "continue" };
+                // Rust's continue would skip the condition at the bottom
+                if self.rust && word == "continue" && self.loops.contains(&(head, true)) {
+                    return unsupported("continue in a do-while");
+                }
+                if self.loops.last().map(|l| l.0) == Some(head) {
+                    self.line(format!("{}{}", word, semi));
+                } else {
+                    let label = self.label(head);
+                    self.line(format!("{} {}{}", word, label, semi));
+                }
+            },
+            Kind::Halt => self.line(if self.rust { "return;".to_string() } else { "halt".to_string() }),
+            Kind::Goto(t) => {
+                if self.rust {
+                    return unsupported("goto");
+                }
+                let label = self.label(t);
+                self.line(format!("goto {}", label));
+            },
+            Kind::Jump(c, x) => {
+                if self.rust {
+                    return unsupported("a computed jump");
+                }
+                let jump = format!("goto {} + {}", s.pc, self.operand(x));
+                match c {
+                    Some(c) => {
+                        let c = self.cond(c);
+                        self.line(format!("if {} {{ {} }}", c, jump));
+                    },
+                    None => self.line(jump),
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+fn printer(stmts: &[Stmt], rust: bool, dialect: Dialect) -> Printer {
+    let mut labels = BTreeSet::new();
+    visit(stmts, &mut vec![], &mut labels);
+
+    Printer { rust, dialect, labels, placed: BTreeSet::new(), loops: vec![], lines: vec![], depth: 0 }
+}
+
+pub fn pseudo(program: &Program, dialect: Dialect) -> String {
+    let stmts = structure(program);
+    let mut p = printer(&stmts, false, dialect);
+    // pseudo-code has an equivalent for everything
+    p.stmts(&stmts).unwrap();
+
+    p.lines.join("\n") + "\n"
+}
+
+// A function over a struct of the registers the program
+// uses. snd and rcv become closures: in the duet dialect
+// rcv returns None to stop the program; in the sound one
+// it returns whether to stop after recovering.
+pub fn rust(program: &Program, dialect: Dialect) -> Result<String, String> {
+    let stmts = structure(program);
+    let mut regs = BTreeSet::new();
+    registers(&stmts, &mut regs);
+    let ops: Vec<Op> = program.iter().map(|x| x.op()).collect();
+
+    let mut params = vec!["r: &mut Registers".to_string()];
+    if ops.contains(&Op::Snd) {
+        params.push("snd: &mut dyn FnMut(i64)".to_string());
+    }
+    if ops.contains(&Op::Rcv) {
+        let returns = if dialect == Dialect::Sound { "bool" } else { "Option<i64>" };
+        params.push(format!("rcv: &mut dyn FnMut() -> {}", returns));
+    }
+
+    let mut p = printer(&stmts, true, dialect);
+    p.depth = 1;
+    p.stmts(&stmts)?;
+
+    let mut lines = vec!["#[derive(Debug, Default)]".to_string(), "pub struct Registers {".to_string()];
+    lines.extend(regs.iter().map(|r| format!("    pub {}: i64,", r)));
+    lines.push("}".to_string());
+    lines.push(String::new());
+    lines.push(format!("pub fn run({}) {{", params.join(", ")));
+    lines.extend(p.lines);
+    lines.push("}".to_string());
+
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use assemble_any;
+    use decompile::pseudo;
+    use decompile::rust;
+    use Dialect;
+
+    #[test]
+    fn it_works() {
+        let countdown = assemble_any("set a 3\nadd b a\nsub a 1\njnz a -2\nsnd b").unwrap();
+        assert_eq!("a = 3\ndo {\n    b += a\n    a -= 1\n} while a != 0\nsend(b)\n", pseudo(&countdown, Dialect::Duet));
+        let code = rust(&countdown, Dialect::Duet).unwrap();
+        assert!(code.contains("pub struct Registers {\n    pub a: i64,\n    pub b: i64,\n}"));
+        assert!(code.contains("pub fn run(r: &mut Registers, snd: &mut dyn FnMut(i64)) {"));
+        assert!(code.contains("        r.a -= 1;\n        if r.a == 0 { break; }\n    }\n    snd(r.b);\n"));
+
+        let branches = assemble_any("jgz a 3\nset b 1\njnz 1 2\nset b 2\nrcv b").unwrap();
+        assert_eq!("if a <= 0 {\n    b = 1\n} else {\n    b = 2\n}\nif b != 0 { recover() }\n", pseudo(&branches, Dialect::Sound));
+        assert!(rust(&branches, Dialect::Sound).unwrap().contains("rcv: &mut dyn FnMut() -> bool) {"));
+
+        let broken = assemble_any("set a 1\nadd a 1\njgz b 2\njnz 1 -2\njgz a 9").unwrap();
+        assert_eq!("a = 1\nloop {\n    a += 1\n    if b > 0 {\n        break\n    }\n}\nif a > 0 {\n    halt\n}\n", pseudo(&broken, Dialect::Duet));
+
+        // into the middle of a loop, which leaves a goto, and to wherever a says
+        let tangled = assemble_any("jgz a 2\nadd a 1\nsub b 1\njnz b -2\njgz a a").unwrap();
+        assert!(pseudo(&tangled, Dialect::Duet).contains("L1:\n    a += 1\n"));
+        assert!(pseudo(&tangled, Dialect::Duet).ends_with("if a > 0 { goto 4 + a }\n"));
+        assert!(rust(&tangled, Dialect::Duet).is_err());
+    }
+}
@@ -0,0 +1,661 @@
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+use decode::Code;
+use decode::Image;
+use decode::REGISTERS;
+use trace::Trace;
+
+pub mod asm;
+pub mod cfg;
+pub mod debug;
+pub mod decode;
+pub mod decompile;
+pub mod network;
+pub mod optimize;
+pub mod trace;
+
+pub use asm::assemble;
+pub use asm::assemble_any;
+pub use asm::AsmError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(char),
+    Value(i64),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Operand::Register(r) => write!(f, "{}", r),
+            Operand::Value(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+// just the opcode, for counting and matching
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Snd,
+    Rcv,
+    Set,
+    Add,
+    Sub,
+    Mul,
+    Mod,
+    Jgz,
+    Jnz,
+    Mac,
+    Dvt,
+    Fct,
+}
+
+// what an operand may be: a register the op writes, or anything
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    Register,
+    Any,
+}
+
+impl Op {
+    pub const ALL: [Op; 12] = [Op::Snd, Op::Rcv, Op::Set, Op::Add, Op::Sub, Op::Mul, Op::Mod, Op::Jgz, Op::Jnz, Op::Mac, Op::Dvt, Op::Fct];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::Snd => "snd",
+            Op::Rcv => "rcv",
+            Op::Set => "set",
+            Op::Add => "add",
+            Op::Sub => "sub",
+            Op::Mul => "mul",
+            Op::Mod => "mod",
+            Op::Jgz => "jgz",
+            Op::Jnz => "jnz",
+            Op::Mac => "mac",
+            Op::Dvt => "dvt",
+            Op::Fct => "fct",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Op> {
+        Op::ALL.iter().find(|op| op.name() == name).cloned()
+    }
+
+    pub fn slots(self) -> &'static [Slot] {
+        const R: Slot = Slot::Register;
+        const X: Slot = Slot::Any;
+        match self {
+            Op::Snd => &[X],
+            Op::Rcv => &[R],
+            Op::Set | Op::Add | Op::Sub | Op::Mul | Op::Mod => &[R, X],
+            Op::Jgz | Op::Jnz => &[X, X],
+            Op::Mac => &[R, X, R],
+            Op::Dvt => &[R, X, R, X],
+            Op::Fct => &[R, R, R, X],
+        }
+    }
+}
+
+// The duet (day 18) and coprocessor (day 23) instruction
+// sets, plus the macro-instructions optimize fuses loops
+// into. Those loops count a register up or down to a
+// bound, so each macro takes its operands in loop order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Instr {
+    Snd(Operand),
+    Rcv(char),
+    Set(char, Operand),
+    Add(char, Operand),
+    Sub(char, Operand),
+    Mul(char, Operand),
+    Mod(char, Operand),
+    Jgz(Operand, Operand),
+    Jnz(Operand, Operand),
+    // mac r x c: r += x * c, leaving c at 0
+    Mac(char, Operand, char),
+    // dvt f d e n: f = 0 if d * k == n for some k from e
+    // up to n, leaving e at n
+    Dvt(char, Operand, char, Operand),
+    // fct f d e n: f = 0 if j * k == n for some j from d
+    // and k from e, both up to n, leaving d and e at n
+    Fct(char, char, char, Operand),
+}
+
+impl Instr {
+    pub fn op(&self) -> Op {
+        match *self {
+            Instr::Snd(_) => Op::Snd,
+            Instr::Rcv(_) => Op::Rcv,
+            Instr::Set(_, _) => Op::Set,
+            Instr::Add(_, _) => Op::Add,
+            Instr::Sub(_, _) => Op::Sub,
+            Instr::Mul(_, _) => Op::Mul,
+            Instr::Mod(_, _) => Op::Mod,
+            Instr::Jgz(_, _) => Op::Jgz,
+            Instr::Jnz(_, _) => Op::Jnz,
+            Instr::Mac(_, _, _) => Op::Mac,
+            Instr::Dvt(_, _, _, _) => Op::Dvt,
+            Instr::Fct(_, _, _, _) => Op::Fct,
+        }
+    }
+
+    // in the order they're written, registers as Operand::Register
+    pub fn operands(&self) -> Vec<Operand> {
+        let reg = Operand::Register;
+        match *self {
+            Instr::Snd(x) => vec![x],
+            Instr::Rcv(r) => vec![reg(r)],
+            Instr::Set(r, y) | Instr::Add(r, y) | Instr::Sub(r, y) | Instr::Mul(r, y) | Instr::Mod(r, y) => vec![reg(r), y],
+            Instr::Jgz(x, y) | Instr::Jnz(x, y) => vec![x, y],
+            Instr::Mac(r, x, c) => vec![reg(r), x, reg(c)],
+            Instr::Dvt(f, d, e, n) => vec![reg(f), d, reg(e), n],
+            Instr::Fct(f, d, e, n) => vec![reg(f), reg(d), reg(e), n],
+        }
+    }
+
+    // the other way round; None unless they fit op's slots
+    pub fn new(op: Op, operands: &[Operand]) -> Option<Instr> {
+        let slots = op.slots();
+        if operands.len() != slots.len() {
+            return None;
+        }
+        let mut regs = vec![];
+        for (x, slot) in operands.iter().zip(slots) {
+            match (*x, *slot) {
+                (Operand::Register(r), Slot::Register) => regs.push(r),
+                (_, Slot::Register) => return None,
+                _ => (),
+            }
+        }
+        let x = operands;
+        let instr = match op {
+            Op::Snd => Instr::Snd(x[0]),
+            Op::Rcv => Instr::Rcv(regs[0]),
+            Op::Set => Instr::Set(regs[0], x[1]),
+            Op::Add => Instr::Add(regs[0], x[1]),
+            Op::Sub => Instr::Sub(regs[0], x[1]),
+            Op::Mul => Instr::Mul(regs[0], x[1]),
+            Op::Mod => Instr::Mod(regs[0], x[1]),
+            Op::Jgz => Instr::Jgz(x[0], x[1]),
+            Op::Jnz => Instr::Jnz(x[0], x[1]),
+            Op::Mac => Instr::Mac(regs[0], x[1], regs[1]),
+            Op::Dvt => Instr::Dvt(regs[0], x[1], regs[1], x[3]),
+            Op::Fct => Instr::Fct(regs[0], regs[1], regs[2], x[3]),
+        };
+
+        Some(instr)
+    }
+
+    // registers this may write to
+    pub fn targets(&self) -> Vec<char> {
+        self.operands().into_iter().zip(self.op().slots()).filter_map(|(x, slot)| match (x, *slot) {
+            (Operand::Register(r), Slot::Register) => Some(r),
+            _ => None,
+        }).collect()
+    }
+}
+
+impl fmt::Display for Instr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.op().name())?;
+        for x in self.operands() {
+            write!(f, " {}", x)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub type Program = Vec<Instr>;
+
+// "a=1", for setting registers from the command line
+pub fn parse_assignment(s: &str) -> Result<(char, i64), String> {
+    let bad = || format!("--set wants REGISTER=VALUE, not {}", s);
+    let mut parts = s.splitn(2, '=');
+    let r = parts.next().unwrap_or("");
+    let v = parts.next().ok_or_else(bad)?.parse().map_err(|_e| bad())?;
+    let mut chars = r.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_lowercase() => Ok((c, v)),
+        _ => Err(bad()),
+    }
+}
+
+// What snd and rcv mean, and which opcodes a program
+// may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    // snd plays a sound, rcv recovers the last one
+    // played if its register isn't zero
+    Sound,
+    // snd sends a value to another program, rcv
+    // waits for one to arrive
+    Duet,
+    // day 23's instructions, with no snd or rcv
+    Coprocessor,
+}
+
+impl Dialect {
+    pub fn name(self) -> &'static str {
+        match self {
+            Dialect::Sound => "sound",
+            Dialect::Duet => "duet",
+            Dialect::Coprocessor => "coprocessor",
+        }
+    }
+
+    // what a program written for it may use; the
+    // macro-instructions are left to optimize
+    pub fn ops(self) -> &'static [Op] {
+        match self {
+            Dialect::Sound | Dialect::Duet => &[Op::Snd, Op::Rcv, Op::Set, Op::Add, Op::Mul, Op::Mod, Op::Jgz],
+            Dialect::Coprocessor => &[Op::Set, Op::Sub, Op::Mul, Op::Jnz],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Running,
+    // stuck on rcv with nothing in the mailbox
+    Waiting,
+    // jumped or ran off either end of the program
+    Halted,
+    // stuck on a mod by zero; stepping retries it
+    Faulted,
+}
+
+// anything a step did that the caller may care about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Executed,
+    Played(i64),
+    Recovered(i64),
+    Sent(i64),
+    Received(i64),
+    Waiting,
+    Halted,
+    Faulted,
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine {
+    image: Arc<Image>,
+    dialect: Dialect,
+    registers: [i64; REGISTERS],
+    // a bit for each register ever set
+    written: u32,
+    pc: i64,
+    status: Status,
+    last_sound: Option<i64>,
+    mailbox: VecDeque<i64>,
+    // by Op
+    executed: [u64; 12],
+    trace: Option<Trace>,
+}
+
+// whether d * k == n for some k from e up to n
+fn divides_in(d: i64, e: i64, n: i64) -> bool {
+    if d == 0 {
+        return n == 0 && e < n;
+    }
+
+    n.wrapping_rem(d) == 0 && e <= n.wrapping_div(d) && n.wrapping_div(d) < n
+}
+
+// whether j * k == n for some j from d and k from e, both up to n
+fn factors_in(d: i64, e: i64, n: i64) -> bool {
+    if n < 1 {
+        return false;
+    }
+    let mut i = 1;
+    while i <= n / i {
+        if n % i == 0 {
+            let (p, q) = (i, n / i);
+            for &(j, k) in [(p, q), (q, p), (-p, -q), (-q, -p)].iter() {
+                if d <= j && j < n && e <= k && k < n {
+                    return true;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    false
+}
+
+impl Machine {
+    // Err if a hand-built Instr names a register outside a to z
+    pub fn new(program: &Program, dialect: Dialect) -> Result<Machine, String> {
+        Ok(Machine::with_image(Arc::new(Image::new(program)?), dialect))
+    }
+
+    // for machines running the same program, decoded once
+    pub fn with_image(image: Arc<Image>, dialect: Dialect) -> Machine {
+        Machine {
+            image,
+            dialect,
+            registers: [0; REGISTERS],
+            written: 0,
+            pc: 0,
+            status: Status::Running,
+            last_sound: None,
+            mailbox: VecDeque::new(),
+            executed: [0; 12],
+            trace: None,
+        }
+    }
+
+    pub fn image(&self) -> &Arc<Image> {
+        &self.image
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.image.program
+    }
+
+    pub fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    // registers that were never set are 0
+    pub fn get(&self, r: char) -> i64 {
+        decode::index(r).map(|i| self.registers[i]).unwrap_or(0)
+    }
+
+    pub fn set(&mut self, r: char, v: i64) -> Result<(), String> {
+        let i = decode::slot(r)?;
+        self.put(i, v);
+        Ok(())
+    }
+
+    #[inline]
+    fn put(&mut self, i: usize, v: i64) {
+        self.registers[i] = v;
+        self.written |= 1 << i;
+    }
+
+    // every register that has been set, in order
+    pub fn registers(&self) -> Vec<(char, i64)> {
+        (0..REGISTERS).filter(|i| self.written & (1 << i) != 0).map(|i| ((b'a' + i as u8) as char, self.registers[i])).collect()
+    }
+
+    pub fn value(&self, x: Operand) -> i64 {
+        match x {
+            Operand::Register(r) => self.get(r),
+            Operand::Value(v) => v,
+        }
+    }
+
+    // times op has run; a waiting rcv doesn't count
+    pub fn executed(&self, op: Op) -> u64 {
+        self.executed[op as usize]
+    }
+
+    pub fn last_sound(&self) -> Option<i64> {
+        self.last_sound
+    }
+
+    // queue a message for rcv, waking the machine if it's waiting
+    pub fn push(&mut self, msg: i64) {
+        self.mailbox.push_back(msg);
+        if self.status == Status::Waiting {
+            self.status = Status::Running;
+        }
+    }
+
+    pub fn mailbox(&self) -> &VecDeque<i64> {
+        &self.mailbox
+    }
+
+    // start recording every step from here on
+    pub fn record(&mut self) {
+        self.trace = Some(Trace::new(self.dialect, self.registers()));
+    }
+
+    pub fn trace(&self) -> Option<&Trace> {
+        self.trace.as_ref()
+    }
+
+    // stops recording
+    pub fn take_trace(&mut self) -> Option<Trace> {
+        self.trace.take()
+    }
+
+    // the steps recorded since the last drain, recording on;
+    // for writing them out as the machine goes
+    pub fn drain_trace(&mut self) -> Vec<trace::Step> {
+        self.trace.as_mut().map(|t| t.steps.drain(..).collect()).unwrap_or_default()
+    }
+
+    fn jump(&mut self, offset: i64) {
+        self.pc = self.pc.wrapping_add(offset).wrapping_sub(1);
+    }
+
+    pub fn step(&mut self) -> Event {
+        if self.status == Status::Halted || self.pc < 0 || self.pc >= self.image.code.len() as i64 {
+            self.status = Status::Halted;
+            return Event::Halted;
+        }
+        let pc = self.pc;
+        let code = self.image.code[pc as usize];
+        let before: Vec<(char, i64)> = match self.trace {
+            Some(_) => self.image.program[pc as usize].targets().into_iter().map(|r| (r, self.get(r))).collect(),
+            None => vec![],
+        };
+        let mut event = Event::Executed;
+        match code {
+            Code::Snd(x) => {
+                let v = x.get(&self.registers);
+                if self.dialect == Dialect::Sound {
+                    self.last_sound = Some(v);
+                    event = Event::Played(v);
+                } else {
+                    event = Event::Sent(v);
+                }
+            },
+            Code::Rcv(r) => {
+                if self.dialect == Dialect::Sound {
+                    if self.registers[r] != 0 {
+                        if let Some(v) = self.last_sound {
+                            event = Event::Recovered(v);
+                        }
+                    }
+                } else {
+                    match self.mailbox.pop_front() {
+                        Some(v) => {
+                            self.put(r, v);
+                            event = Event::Received(v);
+                        },
+                        None => {
+                            self.status = Status::Waiting;
+                            return Event::Waiting;
+                        },
+                    }
+                }
+            },
+            Code::Set(r, y) => {
+                let v = y.get(&self.registers);
+                self.put(r, v);
+            },
+            Code::Add(r, y) => {
+                let v = self.registers[r].wrapping_add(y.get(&self.registers));
+                self.put(r, v);
+            },
+            Code::Sub(r, y) => {
+                let v = self.registers[r].wrapping_sub(y.get(&self.registers));
+                self.put(r, v);
+            },
+            Code::Mul(r, y) => {
+                let v = self.registers[r].wrapping_mul(y.get(&self.registers));
+                self.put(r, v);
+            },
+            Code::Mod(r, y) => {
+                let d = y.get(&self.registers);
+                if d == 0 {
+                    self.status = Status::Faulted;
+                    return Event::Faulted;
+                }
+                let v = self.registers[r].wrapping_rem(d);
+                self.put(r, v);
+            },
+            Code::Jgz(x, y) => {
+                if x.get(&self.registers) > 0 {
+                    let offset = y.get(&self.registers);
+                    self.jump(offset);
+                }
+            },
+            Code::Jnz(x, y) => {
+                if x.get(&self.registers) != 0 {
+                    let offset = y.get(&self.registers);
+                    self.jump(offset);
+                }
+            },
+            Code::Goto(y) => {
+                let offset = y.get(&self.registers);
+                self.jump(offset);
+            },
+            Code::Nop => (),
+            Code::Mac(r, x, c) => {
+                let v = self.registers[r].wrapping_add(x.get(&self.registers).wrapping_mul(self.registers[c]));
+                self.put(r, v);
+                self.put(c, 0);
+            },
+            Code::Dvt(f, d, e, n) => {
+                let n = n.get(&self.registers);
+                if divides_in(d.get(&self.registers), self.registers[e], n) {
+                    self.put(f, 0);
+                }
+                self.put(e, n);
+            },
+            Code::Fct(f, d, e, n) => {
+                let n = n.get(&self.registers);
+                if factors_in(self.registers[d], self.registers[e], n) {
+                    self.put(f, 0);
+                }
+                self.put(d, n);
+                self.put(e, n);
+            },
+        }
+        self.executed[self.image.ops[pc as usize]] += 1;
+        if self.trace.is_some() {
+            let writes = before.into_iter().filter(|&(r, v)| self.get(r) != v).map(|(r, _)| (r, self.get(r))).collect();
+            let step = trace::Step { pc, instr: self.image.program[pc as usize], writes, event };
+            if let Some(ref mut t) = self.trace {
+                t.steps.push(step);
+            }
+        }
+        self.status = Status::Running;
+        self.pc += 1;
+
+        event
+    }
+
+    // steps until halted, waiting or faulted
+    pub fn run(&mut self) {
+        loop {
+            match self.step() {
+                Event::Halted | Event::Waiting | Event::Faulted => return,
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use Dialect;
+    use Event;
+    use Instr;
+    use Machine;
+    use Op;
+    use Operand;
+    use Status;
+    use assemble;
+    use assemble_any;
+
+    #[test]
+    fn it_works() {
+        let src = "set a 1\nadd a 2\nmul a a\nmod a 5\nsnd a\nset a 0\nrcv a\njgz a -1\nset a 1\njgz a -2\n";
+        let program = assemble(src, Dialect::Sound).unwrap();
+        assert_eq!(Instr::Jgz(Operand::Register('a'), Operand::Value(-2)), program[9]);
+        assert_eq!("jgz a -2", program[9].to_string());
+
+        let mut sound = Machine::new(&program, Dialect::Sound).unwrap();
+        let recovered = loop {
+            if let Event::Recovered(v) = sound.step() {
+                break v;
+            }
+        };
+        assert_eq!(4, recovered);
+        assert_eq!(Some(4), sound.last_sound());
+
+        let mut duet = Machine::new(&assemble("snd 1\nrcv a\nadd a 5\nsnd a", Dialect::Duet).unwrap(), Dialect::Duet).unwrap();
+        assert_eq!(Event::Sent(1), duet.step());
+        assert_eq!(Event::Waiting, duet.step());
+        assert_eq!(Status::Waiting, duet.status());
+        assert_eq!(1, duet.pc());
+        duet.push(37);
+        assert_eq!(Status::Running, duet.status());
+        assert_eq!(Event::Received(37), duet.step());
+        duet.run();
+        assert_eq!(Status::Halted, duet.status());
+        assert_eq!(42, duet.get('a'));
+        assert_eq!(2, duet.executed(Op::Snd));
+        assert_eq!(1, duet.executed(Op::Rcv));
+        assert_eq!(vec![('a', 42)], duet.registers());
+
+        // two machines, one decoded program
+        let mut other = Machine::with_image(duet.image().clone(), Dialect::Duet);
+        assert!(Arc::ptr_eq(duet.image(), other.image()));
+        other.push(0);
+        other.run();
+        assert_eq!((Status::Halted, 5), (other.status(), other.get('a')));
+
+        // jumping backwards off the start halts too
+        let mut coproc = Machine::new(&assemble("set b 9\nmul b b\nsub b 1\njnz 1 -10", Dialect::Coprocessor).unwrap(), Dialect::Coprocessor).unwrap();
+        coproc.run();
+        assert_eq!(Status::Halted, coproc.status());
+        assert_eq!(80, coproc.get('b'));
+        assert_eq!(1, coproc.executed(Op::Mul));
+
+        let src = "mac a b c\ndvt f 7 e 91\ndvt g 7 e 91\nfct h d k 97\nset d 2\nset k 2\nfct i d k 91";
+        let fused = assemble_any(src).unwrap();
+        assert_eq!(Instr::Dvt('f', Operand::Value(7), 'e', Operand::Value(91)), fused[1]);
+        assert_eq!("fct h d k 97", fused[3].to_string());
+        let mut macros = Machine::new(&fused, Dialect::Duet).unwrap();
+        for &(r, v) in [('a', 1), ('b', 3), ('c', 4), ('e', 2), ('f', 1), ('g', 1), ('h', 1), ('i', 1), ('d', 2), ('k', 2)].iter() {
+            macros.set(r, v).unwrap();
+        }
+        macros.run();
+        assert_eq!((13, 0), (macros.get('a'), macros.get('c')));
+        // 91 is 7 * 13, but the second dvt starts past 13
+        assert_eq!((0, 1, 91), (macros.get('f'), macros.get('g'), macros.get('e')));
+        assert_eq!((1, 0, 91), (macros.get('h'), macros.get('i'), macros.get('k')));
+
+        // mod by zero faults and retries; overflow wraps
+        let mut faulty = Machine::new(&assemble_any("mod a b\nset c 9223372036854775807\nadd c 1\nmul c 2\nmac d c c").unwrap(), Dialect::Duet).unwrap();
+        assert_eq!(Event::Faulted, faulty.step());
+        assert_eq!((Status::Faulted, 0, 0), (faulty.status(), faulty.pc(), faulty.executed(Op::Mod)));
+        faulty.run();
+        assert_eq!(0, faulty.pc());
+        faulty.set('b', 3).unwrap();
+        faulty.run();
+        assert_eq!(Status::Halted, faulty.status());
+        assert_eq!((0, 0), (faulty.get('c'), faulty.get('d')));
+        let mut big = Machine::new(&assemble_any("fct f d e n").unwrap(), Dialect::Duet).unwrap();
+        big.set('n', i64::MAX).unwrap();
+        big.run();
+        assert_eq!(Status::Halted, big.status());
+
+        assert_eq!(Err("`A` isn't a register (a through z)".to_string()), big.set('A', 1));
+        assert!(Machine::new(&vec![Instr::Rcv('1')], Dialect::Duet).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+
+use std::fmt;
+use std::sync::Arc;
+use decode::Image;
+use Dialect;
+use Event;
+use Machine;
+use Program;
+use Status;
+
+// who hears what each program sends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Topology {
+    // each to the next, and the last to the first
+    Ring,
+    // each to every other
+    Broadcast,
+    // each to the programs listed for it
+    Table(Vec<Vec<usize>>),
+}
+
+impl Topology {
+    // the destinations of each of n programs
+    pub fn routes(&self, n: usize) -> Result<Vec<Vec<usize>>, String> {
+        match *self {
+            Topology::Ring => Ok((0..n).map(|i| vec![(i + 1) % n]).collect()),
+            Topology::Broadcast => Ok((0..n).map(|i| (0..n).filter(|j| *j != i).collect()).collect()),
+            Topology::Table(ref table) => {
+                if table.len() != n {
+                    return Err(format!("the routing table has {} rows for {} programs", table.len(), n));
+                }
+                if let Some(j) = table.iter().flatten().find(|j| **j >= n) {
+                    return Err(format!("there's no program {} to send to", j));
+                }
+                Ok(table.clone())
+            },
+        }
+    }
+}
+
+// what each program did, by number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub sent: Vec<u64>,
+    pub received: Vec<u64>,
+    // waiting on an empty mailbox when everything stopped
+    pub blocked: Vec<usize>,
+    // stuck on a mod by zero
+    pub faulted: Vec<usize>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.sent.len() {
+            let state = if self.blocked.contains(&i) {
+                "blocked"
+            } else if self.faulted.contains(&i) {
+                "faulted"
+            } else {
+                "halted"
+            };
+            writeln!(f, "program {}: sent {}, received {}, {}", i, self.sent[i], self.received[i], state)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Copies of one program in the duet dialect, each with its
+// own registers, passing messages along fixed routes.
+#[derive(Debug, Clone)]
+pub struct Network {
+    machines: Vec<Machine>,
+    routes: Vec<Vec<usize>>,
+    sent: Vec<u64>,
+    received: Vec<u64>,
+}
+
+impl Network {
+    // a program for each set of initial registers
+    pub fn new(program: &Program, registers: &[Vec<(char, i64)>], topology: &Topology) -> Result<Network, String> {
+        let n = registers.len();
+        let routes = topology.routes(n)?;
+        let image = Arc::new(Image::new(program)?);
+        let machines = registers.iter().map(|regs| {
+            let mut machine = Machine::with_image(image.clone(), Dialect::Duet);
+            for &(r, v) in regs {
+                machine.set(r, v)?;
+            }
+            Ok(machine)
+        }).collect::<Result<_, String>>()?;
+
+        Ok(Network { machines, routes, sent: vec![0; n], received: vec![0; n] })
+    }
+
+    pub fn machines(&self) -> &[Machine] {
+        &self.machines
+    }
+
+    // runs program i until it waits, halts or faults, delivering
+    // whatever it sends; returns how many messages that was
+    fn run_until_blocked(&mut self, i: usize) -> u64 {
+        let mut sent = 0;
+        loop {
+            match self.machines[i].step() {
+                Event::Sent(v) => {
+                    for &j in &self.routes[i] {
+                        self.machines[j].push(v);
+                    }
+                    sent += 1;
+                },
+                Event::Received(_) => self.received[i] += 1,
+                Event::Waiting | Event::Halted | Event::Faulted => break,
+                _ => (),
+            }
+        }
+        self.sent[i] += sent;
+
+        sent
+    }
+
+    // Runs the programs in turn until a whole round goes by
+    // without a message sent, when each has halted, faulted
+    // or is waiting on an empty mailbox.
+    pub fn run(&mut self) -> Report {
+        loop {
+            let sent: u64 = (0..self.machines.len()).map(|i| self.run_until_blocked(i)).sum();
+            if sent == 0 {
+                break;
+            }
+        }
+        debug_assert!(self.machines.iter().all(|m| m.status() != Status::Running));
+
+        self.report()
+    }
+
+    fn with_status(&self, status: Status) -> Vec<usize> {
+        (0..self.machines.len()).filter(|i| self.machines[*i].status() == status).collect()
+    }
+
+    pub fn report(&self) -> Report {
+        Report {
+            sent: self.sent.clone(),
+            received: self.received.clone(),
+            blocked: self.with_status(Status::Waiting),
+            faulted: self.with_status(Status::Faulted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assemble;
+    use network::Network;
+    use network::Report;
+    use network::Topology;
+    use Dialect;
+
+    #[test]
+    fn it_works() {
+        // send p, then wait for two values
+        let program = assemble("snd p\nrcv a\nrcv b", Dialect::Duet).unwrap();
+        let registers: Vec<Vec<(char, i64)>> = (1..4).map(|p| vec![('p', p)]).collect();
+
+        let mut ring = Network::new(&program, &registers, &Topology::Ring).unwrap();
+        assert_eq!(Report { sent: vec![1, 1, 1], received: vec![1, 1, 1], blocked: vec![0, 1, 2], faulted: vec![] }, ring.run());
+        assert_eq!((3, 1), (ring.machines()[0].get('a'), ring.machines()[1].get('a')));
+
+        let mut broadcast = Network::new(&program, &registers, &Topology::Broadcast).unwrap();
+        let report = broadcast.run();
+        assert_eq!((vec![2, 2, 2], vec![]), (report.received, report.blocked));
+        assert_eq!((1, 2), (broadcast.machines()[2].get('a'), broadcast.machines()[2].get('b')));
+
+        let table = Topology::Table(vec![vec![1, 2], vec![], vec![]]);
+        let report = Network::new(&program, &registers, &table).unwrap().run();
+        assert_eq!(Report { sent: vec![1, 1, 1], received: vec![0, 1, 1], blocked: vec![0, 1, 2], faulted: vec![] }, report);
+        assert!(report.to_string().starts_with("program 0: sent 1, received 0, blocked\n"));
+
+        // program 1 gets a 0 from program 0 and divides by it,
+        // program 0 gets a 1 and halts
+        let divide = assemble("snd p\nrcv a\nmod p a", Dialect::Duet).unwrap();
+        let report = Network::new(&divide, &[vec![('p', 0)], vec![('p', 1)]], &Topology::Ring).unwrap().run();
+        assert_eq!("program 0: sent 1, received 1, halted\nprogram 1: sent 1, received 1, faulted\n", report.to_string());
+        assert_eq!(vec![1], report.faulted);
+
+        assert!(Network::new(&program, &registers, &Topology::Table(vec![vec![1], vec![0]])).is_err());
+        assert!(Network::new(&program, &registers, &Topology::Table(vec![vec![3], vec![], vec![]])).is_err());
+        let bad = vec![vec![('p', 0)], vec![('P', 1)]];
+        assert_eq!(Some("`P` isn't a register (a through z)".to_string()), Network::new(&program, &bad, &Topology::Ring).err());
+    }
+}
@@ -0,0 +1,194 @@
+
+use std::collections::HashMap;
+use asm;
+use Instr;
+use Operand;
+use Program;
+
+// A loop to look for, written in assembly with capital
+// letters standing in for whichever registers or values
+// the program uses, and the macro-instructions that do
+// the same work in one step.
+struct Fusion {
+    pattern: &'static [&'static str],
+    replacement: &'static [&'static str],
+}
+
+const FUSIONS: &[Fusion] = &[
+    // add x to r, c times over
+    Fusion { pattern: &["add R X", "sub C 1", "jnz C -2"], replacement: &["mac R X C"] },
+    Fusion { pattern: &["sub C 1", "add R X", "jnz C -2"], replacement: &["mac R X C"] },
+    // day 23's inner loop: is d * e ever n, for e up to n?
+    Fusion {
+        pattern: &["set T D", "mul T E", "sub T N", "jnz T 2", "set F 0", "sub E -1", "set T E", "sub T N", "jnz T -8"],
+        replacement: &["dvt F D E N", "set T 0"],
+    },
+    // and the loop around it, over d
+    Fusion {
+        pattern: &["set E K", "dvt F D E N", "set T 0", "sub D -1", "set T D", "sub T N", "jnz T -6"],
+        replacement: &["set E K", "fct F D E N", "set T 0"],
+    },
+];
+
+fn is_placeholder(tok: &str) -> bool {
+    tok.len() == 1 && tok.chars().all(|c| c.is_ascii_uppercase())
+}
+
+// placeholder -> token, if program[at..] fits the pattern;
+// different placeholders never stand for the same token
+fn bind(pattern: &[&str], program: &[Instr], at: usize) -> Option<HashMap<String, String>> {
+    if at + pattern.len() > program.len() {
+        return None;
+    }
+    let mut bound: HashMap<String, String> = HashMap::new();
+    for (line, instr) in pattern.iter().zip(&program[at..]) {
+        let text = instr.to_string();
+        let want: Vec<&str> = line.split_whitespace().collect();
+        let got: Vec<&str> = text.split_whitespace().collect();
+        if want.len() != got.len() {
+            return None;
+        }
+        for (w, g) in want.into_iter().zip(got) {
+            if !is_placeholder(w) {
+                if w != g {
+                    return None;
+                }
+            } else if let Some(b) = bound.get(w) {
+                if b != g {
+                    return None;
+                }
+            } else if bound.values().any(|b| b == g) {
+                return None;
+            } else {
+                bound.insert(w.to_string(), g.to_string());
+            }
+        }
+    }
+
+    Some(bound)
+}
+
+fn fill(replacement: &[&str], bound: &HashMap<String, String>) -> Option<Program> {
+    let lines: Vec<String> = replacement.iter().map(|line| {
+        let toks: Vec<&str> = line.split_whitespace().map(|t| bound.get(t).map(|b| b.as_str()).unwrap_or(t)).collect();
+        toks.join(" ")
+    }).collect();
+
+    asm::assemble_any(&lines.join("\n")).ok()
+}
+
+fn jump_offset(instr: &Instr) -> Option<Operand> {
+    match *instr {
+        Instr::Jgz(_, y) | Instr::Jnz(_, y) => Some(y),
+        _ => None,
+    }
+}
+
+// Swaps program[at..(at + len)] for replacement, moving the
+// other jumps' offsets to suit. None if something outside
+// jumps into the middle of the swapped section.
+fn splice(program: &Program, at: usize, len: usize, replacement: Program) -> Option<Program> {
+    let (at, end, shift) = (at as i64, (at + len) as i64, replacement.len() as i64 - len as i64);
+    let moved = |i: i64| if i >= end { i + shift } else { i };
+    let mut ret = program[..(at as usize)].to_vec();
+    ret.extend(replacement);
+    ret.extend_from_slice(&program[(end as usize)..]);
+    for (i, instr) in program.iter().enumerate() {
+        let i = i as i64;
+        if i >= at && i < end {
+            continue;
+        }
+        if let Some(Operand::Value(offset)) = jump_offset(instr) {
+            let target = i + offset;
+            if target > at && target < end {
+                return None;
+            }
+            let fixed = moved(target) - moved(i);
+            let slot = &mut ret[moved(i) as usize];
+            *slot = match *instr {
+                Instr::Jgz(x, _) => Instr::Jgz(x, Operand::Value(fixed)),
+                Instr::Jnz(x, _) => Instr::Jnz(x, Operand::Value(fixed)),
+                other => other,
+            };
+        }
+    }
+
+    Some(ret)
+}
+
+fn fuse_one(program: &Program) -> Option<Program> {
+    for fusion in FUSIONS {
+        for at in 0..program.len() {
+            let fused = bind(fusion.pattern, program, at)
+                .and_then(|bound| fill(fusion.replacement, &bound))
+                .and_then(|replacement| splice(program, at, fusion.pattern.len(), replacement));
+            if fused.is_some() {
+                return fused;
+            }
+        }
+    }
+
+    None
+}
+
+// Fuses every loop it recognizes into macro-instructions,
+// innermost first, until there's nothing left to fuse.
+// A jump with a register for its offset could land
+// anywhere, so programs with one are left alone.
+pub fn optimize(program: &Program) -> Program {
+    let computed = program.iter().any(|x| matches!(jump_offset(x), Some(Operand::Register(_))));
+    if computed {
+        return program.clone();
+    }
+
+    let mut ret = program.clone();
+    while let Some(fused) = fuse_one(&ret) {
+        ret = fused;
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use assemble;
+    use assemble_any;
+    use optimize::optimize;
+    use Dialect;
+    use Machine;
+    use Op;
+
+    // day 23's program, counting composites from 41 to 126 when a is 1
+    const COPROC: &str = "set b 41\nset c b\njnz a 2\njnz 1 5\nmul b 1\nsub b 0\nset c b\nsub c -85\n\
+        set f 1\nset d 2\nset e 2\nset g d\nmul g e\nsub g b\njnz g 2\nset f 0\nsub e -1\nset g e\nsub g b\njnz g -8\n\
+        sub d -1\nset g d\nsub g b\njnz g -13\njnz f 2\nsub h -1\nset g b\nsub g c\njnz g 2\njnz 1 3\nsub b -17\njnz 1 -23";
+
+    #[test]
+    fn it_works() {
+        let program = assemble(COPROC, Dialect::Coprocessor).unwrap();
+        let fused = optimize(&program);
+        let text: Vec<String> = fused.iter().map(|x| x.to_string()).collect();
+        assert_eq!(["set f 1", "set d 2", "set e 2", "fct f d e b", "set g 0", "jnz f 2", "sub h -1"], text[8..15]);
+        assert_eq!("jnz 1 -12", text[text.len() - 1]);
+
+        let mut slow = Machine::new(&program, Dialect::Duet).unwrap();
+        let mut fast = Machine::new(&fused, Dialect::Duet).unwrap();
+        slow.set('a', 1).unwrap();
+        fast.set('a', 1).unwrap();
+        slow.run();
+        fast.run();
+        assert_eq!(slow.registers(), fast.registers());
+        assert_eq!(4, fast.get('h'));
+        assert_eq!(6, fast.executed(Op::Fct));
+
+        let adds = assemble_any("set a 2\nset c 5\nadd a b\nsub c 1\njnz c -2\njgz a -4").unwrap();
+        assert_eq!("mac a b c", optimize(&adds)[2].to_string());
+        assert_eq!("jgz a -2", optimize(&adds)[3].to_string());
+
+        // the loop can't be fused while something jumps into it
+        let entered = assemble_any("jnz a 3\nadd a b\nsub c 1\njnz c -2").unwrap();
+        assert_eq!(entered, optimize(&entered));
+        let computed = assemble_any("add a b\nsub c 1\njnz c -2\njgz a a").unwrap();
+        assert_eq!(computed, optimize(&computed));
+    }
+}
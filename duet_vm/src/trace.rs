@@ -0,0 +1,512 @@
+
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use Dialect;
+use Event;
+use Instr;
+use Op;
+use Operand;
+
+// One executed instruction. Waiting on rcv isn't a step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub pc: i64,
+    pub instr: Instr,
+    // registers the instruction changed, with their new values
+    pub writes: Vec<(char, i64)>,
+    pub event: Event,
+}
+
+// Everything a machine executed after recording started,
+// along with the registers it started from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub dialect: Dialect,
+    pub registers: Vec<(char, i64)>,
+    pub steps: Vec<Step>,
+}
+
+const MAGIC: &[u8] = b"DUETTRC\x01";
+
+impl Trace {
+    pub fn new(dialect: Dialect, registers: Vec<(char, i64)>) -> Trace {
+        Trace { dialect, registers, steps: vec![] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    // the registers as they stood before step n ran
+    pub fn registers_at(&self, n: usize) -> Vec<(char, i64)> {
+        let mut regs = self.registers.clone();
+        for step in self.steps.iter().take(n) {
+            for &(r, v) in &step.writes {
+                match regs.iter_mut().find(|x| x.0 == r) {
+                    Some(x) => x.1 = v,
+                    None => regs.push((r, v)),
+                }
+            }
+        }
+        regs.sort();
+
+        regs
+    }
+
+    // every value r is given, starting with its initial one
+    pub fn history(&self, r: char) -> Vec<i64> {
+        let first = self.registers.iter().find(|x| x.0 == r).map(|x| x.1).unwrap_or(0);
+        let writes = self.steps.iter().flat_map(|s| s.writes.iter()).filter(|x| x.0 == r).map(|x| x.1);
+        Some(first).into_iter().chain(writes).collect()
+    }
+
+    pub fn write_binary(&self, out: &mut dyn Write) -> io::Result<()> {
+        self.write(TraceWriter::new(out, false, self.dialect, &self.registers)?)
+    }
+
+    // A header line, then a flat object per step, register
+    // writes keyed by the register's name.
+    pub fn write_json(&self, out: &mut dyn Write) -> io::Result<()> {
+        self.write(TraceWriter::new(out, true, self.dialect, &self.registers)?)
+    }
+
+    fn write<W: Write>(&self, mut w: TraceWriter<W>) -> io::Result<()> {
+        for step in &self.steps {
+            w.step(step)?;
+        }
+
+        w.flush()
+    }
+
+    // either format, told apart by the binary one's magic number
+    pub fn read(input: &mut dyn Read) -> Result<Trace, String> {
+        let mut bytes = vec![];
+        input.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+        if bytes.starts_with(MAGIC) {
+            read_binary(&bytes[MAGIC.len()..])
+        } else {
+            let text = String::from_utf8(bytes).map_err(|_e| "not a trace".to_string())?;
+            read_json(&text)
+        }
+    }
+}
+
+// Writes a trace step by step as it's recorded, so a long
+// run needn't keep every step in memory, and an interrupted
+// one keeps whatever was flushed.
+pub struct TraceWriter<W: Write> {
+    out: io::BufWriter<W>,
+    json: bool,
+    steps: usize,
+}
+
+impl<W: Write> TraceWriter<W> {
+    // writes the header: the dialect and starting registers
+    pub fn new(out: W, json: bool, dialect: Dialect, registers: &[(char, i64)]) -> io::Result<TraceWriter<W>> {
+        let mut out = io::BufWriter::new(out);
+        if json {
+            let mut header = vec![format!("\"dialect\":\"{}\"", dialect.name())];
+            header.extend(registers.iter().map(|&(r, v)| format!("\"{}\":{}", r, v)));
+            writeln!(out, "{{{}}}", header.join(","))?;
+        } else {
+            let mut buf = MAGIC.to_vec();
+            buf.push(dialect_code(dialect));
+            write_registers(&mut buf, registers);
+            out.write_all(&buf)?;
+        }
+
+        Ok(TraceWriter { out, json, steps: 0 })
+    }
+
+    pub fn step(&mut self, step: &Step) -> io::Result<()> {
+        if self.json {
+            let mut fields = vec![format!("\"step\":{}", self.steps), format!("\"pc\":{}", step.pc), format!("\"instr\":\"{}\"", step.instr)];
+            if let (_, Some(v)) = event_code(step.event) {
+                fields.push(format!("\"{}\":{}", event_name(step.event), v));
+            }
+            fields.extend(step.writes.iter().map(|&(r, v)| format!("\"{}\":{}", r, v)));
+            writeln!(self.out, "{{{}}}", fields.join(","))?;
+        } else {
+            let mut buf = vec![];
+            let (kind, value) = event_code(step.event);
+            buf.push(kind);
+            write_int(&mut buf, step.pc);
+            write_instr(&mut buf, step.instr);
+            if let Some(v) = value {
+                write_int(&mut buf, v);
+            }
+            write_registers(&mut buf, &step.writes);
+            self.out.write_all(&buf)?;
+        }
+        self.steps += 1;
+
+        Ok(())
+    }
+
+    // steps written so far
+    pub fn len(&self) -> usize {
+        self.steps
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps == 0
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+// where two traces part ways
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Divergence {
+    // the first step that differs
+    Step(usize),
+    // one trace is a prefix of the other; the shorter length
+    Length(usize),
+    // register, and the index into its history
+    History(char, usize),
+}
+
+// step by step, pc, instruction, writes and all
+pub fn diff(a: &Trace, b: &Trace) -> Option<Divergence> {
+    if a.registers_at(0) != b.registers_at(0) {
+        return Some(Divergence::Step(0));
+    }
+    match a.steps.iter().zip(b.steps.iter()).position(|(x, y)| x != y) {
+        Some(i) => Some(Divergence::Step(i)),
+        None if a.len() != b.len() => Some(Divergence::Length(a.len().min(b.len()))),
+        None => None,
+    }
+}
+
+// Only the values the given registers take on, however
+// many steps it took to get to them. A hand-optimized
+// program should agree with the original on these.
+pub fn diff_registers(a: &Trace, b: &Trace, registers: &[char]) -> Option<Divergence> {
+    for r in registers {
+        let (x, y) = (a.history(*r), b.history(*r));
+        if x != y {
+            let i = x.iter().zip(y.iter()).position(|(p, q)| p != q).unwrap_or(x.len().min(y.len()));
+            return Some(Divergence::History(*r, i));
+        }
+    }
+
+    None
+}
+
+fn dialect_code(d: Dialect) -> u8 {
+    match d {
+        Dialect::Sound => 0,
+        Dialect::Duet => 1,
+        Dialect::Coprocessor => 2,
+    }
+}
+
+fn event_code(e: Event) -> (u8, Option<i64>) {
+    match e {
+        Event::Played(v) => (1, Some(v)),
+        Event::Recovered(v) => (2, Some(v)),
+        Event::Sent(v) => (3, Some(v)),
+        Event::Received(v) => (4, Some(v)),
+        _ => (0, None),
+    }
+}
+
+fn event_name(e: Event) -> &'static str {
+    match e {
+        Event::Played(_) => "played",
+        Event::Recovered(_) => "recovered",
+        Event::Sent(_) => "sent",
+        Event::Received(_) => "received",
+        _ => "executed",
+    }
+}
+
+fn event_from(kind: u8, v: i64) -> Option<Event> {
+    match kind {
+        0 => Some(Event::Executed),
+        1 => Some(Event::Played(v)),
+        2 => Some(Event::Recovered(v)),
+        3 => Some(Event::Sent(v)),
+        4 => Some(Event::Received(v)),
+        _ => None,
+    }
+}
+
+// zigzag then LEB128, so small negative offsets stay small
+fn write_int(buf: &mut Vec<u8>, v: i64) {
+    let mut z = ((v << 1) ^ (v >> 63)) as u64;
+    while z >= 0x80 {
+        buf.push((z as u8) | 0x80);
+        z >>= 7;
+    }
+    buf.push(z as u8);
+}
+
+fn write_registers(buf: &mut Vec<u8>, regs: &[(char, i64)]) {
+    buf.push(regs.len() as u8);
+    for &(r, v) in regs {
+        buf.push(r as u8);
+        write_int(buf, v);
+    }
+}
+
+// the opcode, then a byte per register or a tagged int per value
+fn write_instr(buf: &mut Vec<u8>, instr: Instr) {
+    let op = Op::ALL.iter().position(|x| *x == instr.op()).unwrap() as u8;
+    buf.push(op);
+    for x in instr.operands() {
+        match x {
+            Operand::Register(r) => buf.push(r as u8),
+            Operand::Value(v) => {
+                buf.push(0);
+                write_int(buf, v);
+            },
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let b = *self.bytes.get(self.pos).ok_or_else(|| "trace cut short".to_string())?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn int(&mut self) -> Result<i64, String> {
+        let mut z = 0u64;
+        let mut shift = 0;
+        loop {
+            let b = self.byte()?;
+            if shift > 63 {
+                return Err("bad number in trace".to_string());
+            }
+            z |= ((b & 0x7f) as u64) << shift;
+            shift += 7;
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+
+        Ok(((z >> 1) as i64) ^ -((z & 1) as i64))
+    }
+
+    fn register(&mut self) -> Result<char, String> {
+        match self.byte()? {
+            b @ b'a'..=b'z' => Ok(b as char),
+            b => Err(format!("bad register {} in trace", b)),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        match self.byte()? {
+            0 => self.int().map(Operand::Value),
+            b @ b'a'..=b'z' => Ok(Operand::Register(b as char)),
+            b => Err(format!("bad operand {} in trace", b)),
+        }
+    }
+
+    fn registers(&mut self) -> Result<Vec<(char, i64)>, String> {
+        let n = self.byte()?;
+        (0..n).map(|_i| Ok((self.register()?, self.int()?))).collect()
+    }
+
+    fn instr(&mut self) -> Result<Instr, String> {
+        let op = *Op::ALL.get(self.byte()? as usize).ok_or_else(|| "bad opcode in trace".to_string())?;
+        let operands = op.slots().iter().map(|_s| self.operand()).collect::<Result<Vec<Operand>, String>>()?;
+        Instr::new(op, &operands).ok_or_else(|| format!("bad operands for {} in trace", op.name()))
+    }
+}
+
+fn read_binary(bytes: &[u8]) -> Result<Trace, String> {
+    let mut r = Reader { bytes, pos: 0 };
+    let dialect = match r.byte()? {
+        0 => Dialect::Sound,
+        1 => Dialect::Duet,
+        2 => Dialect::Coprocessor,
+        _ => return Err("bad dialect in trace".to_string()),
+    };
+    let mut trace = Trace::new(dialect, r.registers()?);
+    while r.pos < bytes.len() {
+        let kind = r.byte()?;
+        let pc = r.int()?;
+        let instr = r.instr()?;
+        let value = if kind == 0 { 0 } else { r.int()? };
+        let event = event_from(kind, value).ok_or_else(|| "bad event in trace".to_string())?;
+        let writes = r.registers()?;
+        trace.steps.push(Step { pc, instr, writes, event });
+    }
+
+    Ok(trace)
+}
+
+// The flat objects write_json makes: string or integer
+// values, no nesting, no escapes.
+fn parse_object(line: &str) -> Option<Vec<(&str, &str)>> {
+    let inner = line.trim().strip_prefix('{')?.strip_suffix('}')?;
+    let mut fields = vec![];
+    let mut rest = inner;
+    while !rest.is_empty() {
+        let after_key = rest.strip_prefix('"')?;
+        let end = after_key.find('"')?;
+        let key = &after_key[..end];
+        let after_colon = after_key[(end + 1)..].strip_prefix(':')?;
+        let (value, tail) = if let Some(s) = after_colon.strip_prefix('"') {
+            let end = s.find('"')?;
+            (&s[..end], &s[(end + 1)..])
+        } else {
+            let end = after_colon.find(',').unwrap_or(after_colon.len());
+            (&after_colon[..end], &after_colon[end..])
+        };
+        fields.push((key, value));
+        rest = tail.strip_prefix(',').unwrap_or(tail);
+        if !tail.is_empty() && rest.len() == tail.len() {
+            return None;
+        }
+    }
+
+    Some(fields)
+}
+
+fn json_register(key: &str) -> Option<char> {
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_lowercase() => Some(c),
+        _ => None,
+    }
+}
+
+fn read_json(text: &str) -> Result<Trace, String> {
+    let mut lines = text.lines().enumerate().filter(|x| !x.1.trim().is_empty());
+    let bad = |i: usize| format!("line {}: not a trace step", i + 1);
+    let int = |i: usize, v: &str| v.parse::<i64>().map_err(|_e| bad(i));
+
+    let (i, header) = lines.next().ok_or_else(|| "empty trace".to_string())?;
+    let mut trace = Trace::new(Dialect::Duet, vec![]);
+    for (k, v) in parse_object(header).ok_or_else(|| bad(i))? {
+        match (k, json_register(k)) {
+            ("dialect", _) if v == "sound" => trace.dialect = Dialect::Sound,
+            ("dialect", _) if v == "duet" => trace.dialect = Dialect::Duet,
+            ("dialect", _) if v == "coprocessor" => trace.dialect = Dialect::Coprocessor,
+            (_, Some(r)) => trace.registers.push((r, int(i, v)?)),
+            _ => return Err(bad(i)),
+        }
+    }
+
+    for (i, line) in lines {
+        let (mut pc, mut instr, mut event, mut writes) = (None, None, Event::Executed, vec![]);
+        for (k, v) in parse_object(line).ok_or_else(|| bad(i))? {
+            match (k, json_register(k)) {
+                ("step", _) => (),
+                ("pc", _) => pc = Some(int(i, v)?),
+                ("instr", _) => instr = ::asm::assemble_any(v).ok().and_then(|p| p.first().cloned()),
+                ("played", _) => event = Event::Played(int(i, v)?),
+                ("recovered", _) => event = Event::Recovered(int(i, v)?),
+                ("sent", _) => event = Event::Sent(int(i, v)?),
+                ("received", _) => event = Event::Received(int(i, v)?),
+                (_, Some(r)) => writes.push((r, int(i, v)?)),
+                _ => return Err(bad(i)),
+            }
+        }
+        match (pc, instr) {
+            (Some(pc), Some(instr)) => trace.steps.push(Step { pc, instr, writes, event }),
+            _ => return Err(bad(i)),
+        }
+    }
+
+    Ok(trace)
+}
+
+#[cfg(test)]
+mod tests {
+    use assemble_any;
+    use trace::diff;
+    use trace::diff_registers;
+    use trace::Divergence;
+    use trace::Trace;
+    use trace::TraceWriter;
+    use Dialect;
+    use Event;
+    use Machine;
+
+    fn record(src: &str, a: i64) -> Trace {
+        let mut m = Machine::new(&assemble_any(src).unwrap(), Dialect::Duet).unwrap();
+        m.set('a', a).unwrap();
+        m.record();
+        m.push(7);
+        m.run();
+        m.take_trace().unwrap()
+    }
+
+    #[test]
+    fn it_works() {
+        let src = "rcv b\nset c 3\nadd b -200\nsub c 1\njgz c -2\nsnd b";
+        let trace = record(src, 1);
+        assert_eq!(12, trace.len());
+        assert_eq!(vec![('a', 1)], trace.registers);
+        assert_eq!(Event::Received(7), trace.steps[0].event);
+        assert_eq!(vec![('b', 7)], trace.steps[0].writes);
+        assert_eq!((4, 2), (trace.steps[4].pc, trace.steps[5].pc));
+        assert_eq!(Event::Sent(-593), trace.steps[11].event);
+        assert_eq!(vec![('a', 1), ('b', -193), ('c', 2)], trace.registers_at(5));
+        assert_eq!(vec![0, 7, -193, -393, -593], trace.history('b'));
+
+        let mut bin = vec![];
+        trace.write_binary(&mut bin).unwrap();
+        assert_eq!(trace, Trace::read(&mut &bin[..]).unwrap());
+        let mut json = vec![];
+        trace.write_json(&mut json).unwrap();
+        let text = String::from_utf8(json).unwrap();
+        assert_eq!(Some("{\"dialect\":\"duet\",\"a\":1}"), text.lines().next());
+        assert_eq!(Some("{\"step\":0,\"pc\":0,\"instr\":\"rcv b\",\"received\":7,\"b\":7}"), text.lines().nth(1));
+        assert_eq!(trace, Trace::read(&mut text.as_bytes()).unwrap());
+        assert!(bin.len() < text.len() / 4);
+        assert!(Trace::read(&mut &bin[..(bin.len() - 1)]).is_err());
+        assert!(Trace::read(&mut "{\"dialect\":\"duet\"}\n{\"pc\":0}".as_bytes()).is_err());
+
+        assert_eq!(None, diff(&trace, &trace));
+        assert_eq!(Some(Divergence::Step(0)), diff(&trace, &record(src, 2)));
+        let unrolled = record("rcv b\nset c 0\nadd b -200\nadd b -200\nadd b -200\nsnd b", 1);
+        assert_eq!(Some(Divergence::Step(1)), diff(&trace, &unrolled));
+        assert_eq!(None, diff_registers(&trace, &unrolled, &['a', 'b']));
+        assert_eq!(Some(Divergence::History('c', 1)), diff_registers(&trace, &unrolled, &['c']));
+        let mut short = trace.clone();
+        short.steps.truncate(4);
+        assert_eq!(Some(Divergence::Length(4)), diff(&trace, &short));
+
+        // streamed a few steps at a time, the same bytes come out
+        let mut m = Machine::new(&assemble_any(src).unwrap(), Dialect::Duet).unwrap();
+        m.set('a', 1).unwrap();
+        let mut streamed = vec![];
+        {
+            let mut w = TraceWriter::new(&mut streamed, false, Dialect::Duet, &m.registers()).unwrap();
+            m.record();
+            m.push(7);
+            for _i in 0..5 {
+                m.step();
+            }
+            for step in m.drain_trace() {
+                w.step(&step).unwrap();
+            }
+            assert!(m.trace().unwrap().is_empty());
+            m.run();
+            for step in m.drain_trace() {
+                w.step(&step).unwrap();
+            }
+            assert_eq!(12, w.len());
+            w.flush().unwrap();
+        }
+        assert_eq!(bin, streamed);
+    }
+}
@@ -0,0 +1,399 @@
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::Add;
+use std::ops::Index;
+use std::ops::IndexMut;
+use std::ops::Neg;
+use std::ops::Sub;
+
+// Anything usable as a coordinate. Coordinates are signed
+// so that stepping off the edge of a map is just a point
+// that isn't in the grid.
+pub trait Coord: Copy + Debug + Default + Eq + Ord + Hash + Add<Output=Self> + Sub<Output=Self> + Neg<Output=Self> {
+    fn one() -> Self;
+    fn abs(self) -> Self;
+    // None if negative or too big
+    fn to_index(self) -> Option<usize>;
+    fn from_index(i: usize) -> Self;
+}
+
+macro_rules! coord {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                fn one() -> $t { 1 }
+                fn abs(self) -> $t { <$t>::abs(self) }
+                fn to_index(self) -> Option<usize> {
+                    if self < 0 { None } else { Some(self as usize) }
+                }
+                fn from_index(i: usize) -> $t { i as $t }
+            }
+        )*
+    }
+}
+
+coord!(i32, i64, isize);
+
+// x grows to the right and y grows downward, same
+// as reading a map line by line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T: Coord = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coord> Point<T> {
+    pub fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+
+    pub fn origin() -> Point<T> {
+        Point::default()
+    }
+
+    pub fn step(&self, dir: Direction) -> Point<T> {
+        *self + dir.delta()
+    }
+
+    pub fn manhattan(&self, other: &Point<T>) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    // up, right, down, left
+    pub fn neighbors4(&self) -> Vec<Point<T>> {
+        Direction::ALL.iter().map(|d| self.step(*d)).collect()
+    }
+
+    // clockwise from up, diagonals included
+    pub fn neighbors8(&self) -> Vec<Point<T>> {
+        let mut ret = vec![];
+        for d in Direction::ALL.iter() {
+            let p = self.step(*d);
+            ret.push(p);
+            ret.push(p.step(d.turn_right()));
+        }
+
+        ret
+    }
+}
+
+impl<T: Coord> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Coord> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    // clockwise from up
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    pub fn turn_left(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        self.turn_left().reverse()
+    }
+
+    pub fn reverse(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        self == Direction::Up || self == Direction::Down
+    }
+
+    // one step in this direction
+    pub fn delta<T: Coord>(self) -> Point<T> {
+        let one = T::one();
+        let zero = T::default();
+        match self {
+            Direction::Up => Point::new(zero, -one),
+            Direction::Down => Point::new(zero, one),
+            Direction::Left => Point::new(-one, zero),
+            Direction::Right => Point::new(one, zero),
+        }
+    }
+}
+
+// A fixed size grid stored row by row. Points
+// outside of it just aren't there.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<V> {
+    width: usize,
+    height: usize,
+    cells: Vec<V>,
+}
+
+impl<V: Clone> Grid<V> {
+    pub fn new(width: usize, height: usize, fill: V) -> Grid<V> {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+}
+
+impl<V> Grid<V> {
+    // cells.len() must be a multiple of width
+    pub fn from_cells(width: usize, cells: Vec<V>) -> Grid<V> {
+        assert!(width > 0 && cells.len().is_multiple_of(width), "{} cells don't fill rows of {}", cells.len(), width);
+        Grid { width, height: cells.len() / width, cells }
+    }
+
+    pub fn from_fn<T: Coord, F: Fn(Point<T>) -> V>(width: usize, height: usize, f: F) -> Grid<V> {
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(f(Point::new(T::from_index(x), T::from_index(y))));
+            }
+        }
+
+        Grid { width, height, cells }
+    }
+
+    // One cell per character. Short lines are padded
+    // out to the longest one with f(' ').
+    pub fn parse<F: Fn(char) -> V>(map: &str, f: F) -> Grid<V> {
+        let lines: Vec<Vec<char>> = map.lines().map(|l| l.chars().collect()).collect();
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for line in lines.iter() {
+            for x in 0..width {
+                cells.push(f(*line.get(x).unwrap_or(&' ')));
+            }
+        }
+
+        Grid { width, height: lines.len(), cells }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn offset<T: Coord>(&self, p: Point<T>) -> Option<usize> {
+        match (p.x.to_index(), p.y.to_index()) {
+            (Some(x), Some(y)) if x < self.width && y < self.height => Some(y * self.width + x),
+            _ => None,
+        }
+    }
+
+    pub fn contains<T: Coord>(&self, p: Point<T>) -> bool {
+        self.offset(p).is_some()
+    }
+
+    pub fn get<T: Coord>(&self, p: Point<T>) -> Option<&V> {
+        self.offset(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut<T: Coord>(&mut self, p: Point<T>) -> Option<&mut V> {
+        match self.offset(p) {
+            Some(i) => Some(&mut self.cells[i]),
+            None => None,
+        }
+    }
+
+    // panics if p is outside the grid
+    pub fn set<T: Coord>(&mut self, p: Point<T>, v: V) {
+        let i = self.offset(p).unwrap_or_else(|| panic!("{:?} is outside the grid", p));
+        self.cells[i] = v;
+    }
+
+    // every point, row by row
+    pub fn points<T: Coord>(&self) -> Vec<Point<T>> {
+        let mut ret = Vec::with_capacity(self.cells.len());
+        for y in 0..self.height {
+            for x in 0..self.width {
+                ret.push(Point::new(T::from_index(x), T::from_index(y)));
+            }
+        }
+
+        ret
+    }
+
+    pub fn values(&self) -> std::slice::Iter<'_, V> {
+        self.cells.iter()
+    }
+
+    pub fn rows(&self) -> std::slice::Chunks<'_, V> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn neighbors4<T: Coord>(&self, p: Point<T>) -> Vec<Point<T>> {
+        p.neighbors4().into_iter().filter(|n| self.contains(*n)).collect()
+    }
+
+    pub fn neighbors8<T: Coord>(&self, p: Point<T>) -> Vec<Point<T>> {
+        p.neighbors8().into_iter().filter(|n| self.contains(*n)).collect()
+    }
+
+    // back to a character map, one line per row
+    pub fn render<F: Fn(&V) -> char>(&self, f: F) -> String {
+        let rows: Vec<String> = self.rows().map(|row| row.iter().map(&f).collect()).collect();
+        rows.join("\n")
+    }
+}
+
+// grid[p] panics if p is outside the grid
+impl<T: Coord, V> Index<Point<T>> for Grid<V> {
+    type Output = V;
+
+    fn index(&self, p: Point<T>) -> &V {
+        self.get(p).unwrap_or_else(|| panic!("{:?} is outside the grid", p))
+    }
+}
+
+impl<T: Coord, V> IndexMut<Point<T>> for Grid<V> {
+    fn index_mut(&mut self, p: Point<T>) -> &mut V {
+        match self.offset(p) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("{:?} is outside the grid", p),
+        }
+    }
+}
+
+// An unbounded grid keeping only the cells that have been
+// set. Everything else reads as the default value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashGrid<T: Coord, V> {
+    cells: HashMap<Point<T>, V>,
+    default: V,
+}
+
+impl<T: Coord, V: Clone> HashGrid<T, V> {
+    pub fn new(default: V) -> HashGrid<T, V> {
+        HashGrid { cells: HashMap::new(), default }
+    }
+
+    // Every character gets a cell, with the top
+    // left one at the origin
+    pub fn parse<F: Fn(char) -> V>(map: &str, default: V, f: F) -> HashGrid<T, V> {
+        let mut grid = HashGrid::new(default);
+        for (y, line) in map.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                grid.set(Point::new(T::from_index(x), T::from_index(y)), f(c));
+            }
+        }
+
+        grid
+    }
+
+    pub fn get(&self, p: Point<T>) -> &V {
+        self.cells.get(&p).unwrap_or(&self.default)
+    }
+
+    pub fn get_mut(&mut self, p: Point<T>) -> &mut V {
+        let default = &self.default;
+        self.cells.entry(p).or_insert_with(|| default.clone())
+    }
+
+    pub fn set(&mut self, p: Point<T>, v: V) {
+        self.cells.insert(p, v);
+    }
+
+    pub fn contains(&self, p: Point<T>) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    // cells that have been set, in no particular order
+    pub fn iter(&self) -> std::collections::hash_map::Iter<'_, Point<T>, V> {
+        self.cells.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Direction;
+    use Grid;
+    use HashGrid;
+    use Point;
+
+    #[test]
+    fn it_works() {
+        let p: Point<i64> = Point::new(2, -3);
+        assert_eq!(5, p.manhattan(&Point::origin()));
+        assert_eq!(Point::new(2, -4), p.step(Direction::Up));
+        assert_eq!(Point::new(3, -3), p + Direction::Right.delta());
+        assert_eq!(Point::new(1, -2), p - Point::new(1, -1));
+        assert_eq!(4, p.neighbors4().len());
+        assert_eq!(8, p.neighbors8().len());
+        assert!(p.neighbors8().contains(&Point::new(1, -4)));
+        assert!(!p.neighbors8().contains(&p));
+
+        let mut d = Direction::Up;
+        for _i in 0..4 {
+            assert_eq!(d, d.turn_left().turn_right());
+            assert_eq!(d.reverse(), d.turn_left().turn_left());
+            d = d.turn_right();
+        }
+        assert_eq!(Direction::Right, Direction::Up.turn_right());
+        assert!(Direction::Down.is_vertical());
+
+        let g = Grid::parse("#.\n.##\n", |c| c == '#');
+        assert_eq!((3, 2), (g.width(), g.height()));
+        assert_eq!(Some(&true), g.get(Point::new(0, 0)));
+        assert_eq!(Some(&false), g.get(Point::new(2, 0)));
+        assert_eq!(None, g.get(Point::new(-1, 0)));
+        assert_eq!(None, g.get(Point::new(0, 2)));
+        assert_eq!(2, g.neighbors4(Point::new(0, 0)).len());
+        assert_eq!(5, g.neighbors8(Point::new(1, 0)).len());
+        assert_eq!(6, g.points::<i32>().len());
+        assert_eq!(vec![&[true, false, false][..], &[false, true, true][..]], g.rows().collect::<Vec<_>>());
+        assert_eq!(3, g.values().filter(|x| **x).count());
+        assert_eq!("#..\n.##", g.render(|x| if *x { '#' } else { '.' }));
+
+        let mut h = Grid::from_fn(2, 2, |p: Point| p.x + p.y);
+        h.set(Point::new(1, 1), 5);
+        h[Point::new(1, 1)] += 2;
+        assert_eq!(7, h[Point::new(1, 1)]);
+        *h.get_mut(Point::new(0, 0)).unwrap() += 1;
+        assert_eq!(Grid::from_cells(2, vec![1, 1, 1, 7]), h);
+
+        let mut hg: HashGrid<i32, char> = HashGrid::parse("ab\nc", '.', |c| c);
+        assert_eq!(3, hg.len());
+        assert_eq!(&'c', hg.get(Point::new(0, 1)));
+        assert_eq!(&'.', hg.get(Point::new(-5, 9)));
+        *hg.get_mut(Point::new(-5, 9)) = 'z';
+        assert!(hg.contains(Point::new(-5, 9)));
+        assert_eq!(4, hg.iter().count());
+    }
+}
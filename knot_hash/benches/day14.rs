@@ -0,0 +1,73 @@
+// Day 14's workload, 128 row hashes of one key, through the
+// allocating twist the crate used to have, the in-place
+// State, the fixed size State256 and hash_many.
+//
+//   cargo bench -p knot_hash
+
+extern crate knot_hash;
+
+use std::time::Duration;
+use std::time::Instant;
+use knot_hash::KnotHashParams;
+
+const RUNS: u32 = 20;
+
+fn time<F: Fn() -> usize>(name: &str, f: F) -> Duration {
+    let mut check = 0;
+    let start = Instant::now();
+    for _i in 0..RUNS {
+        check += f();
+    }
+    let each = start.elapsed() / RUNS;
+    println!("{:<10} {:>10.3}ms per key ({})", name, each.as_secs_f64() * 1000.0, check);
+
+    each
+}
+
+// The twist as it was: the span copied out into a Vec,
+// reversed there and written back, with the same 64 rounds
+// and dense hash around it.
+fn allocating_hash(input: &[u8]) -> Vec<u8> {
+    let mut list: Vec<u16> = (0..256).collect();
+    let (mut pos, mut skip_size) = (0, 0);
+    let mut lengths = input.to_vec();
+    lengths.extend_from_slice(&[17, 31, 73, 47, 23]);
+    for _round in 0..64 {
+        for &length in &lengths {
+            let mut tmp = vec![0; length as usize];
+            for (i, t) in tmp.iter_mut().enumerate() {
+                *t = list[(pos + i) % list.len()];
+            }
+            tmp.reverse();
+            for (i, t) in tmp.iter().enumerate() {
+                let j = (pos + i) % list.len();
+                list[j] = *t;
+            }
+            pos = (pos + (length as usize) + skip_size) % list.len();
+            skip_size += 1;
+        }
+    }
+
+    list.chunks(16).map(|block| block.iter().fold(0, |acc, x| acc ^ (*x as u8))).collect()
+}
+
+fn main() {
+    let rows: Vec<String> = (0..128).map(|i| format!("flqrgnkx-{}", i)).collect();
+    let params = KnotHashParams::new();
+    assert_eq!(allocating_hash(rows[0].as_bytes()), knot_hash::hash_bytes(rows[0].as_bytes()).to_vec());
+    let baseline = time("allocating", || {
+        rows.iter().map(|r| allocating_hash(r.as_bytes())[0] as usize).sum()
+    });
+    let in_place = time("State", || {
+        rows.iter().map(|r| params.run(r.as_bytes()).unwrap().dense()[0] as usize).sum()
+    });
+    let fast = time("State256", || {
+        rows.iter().map(|r| knot_hash::hash_bytes(r.as_bytes())[0] as usize).sum()
+    });
+    let many = time("hash_many", || {
+        knot_hash::hash_many(&rows).iter().map(|d| d[0] as usize).sum()
+    });
+    let speedup = |d: Duration| baseline.as_secs_f64() / d.as_secs_f64();
+    println!("against the allocating twist: State {:.1}x, State256 {:.1}x, hash_many {:.1}x",
+        speedup(in_place), speedup(fast), speedup(many));
+}
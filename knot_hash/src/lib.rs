@@ -1,34 +1,95 @@
 
+use std::fmt;
 use std::fmt::Write;
+use std::hash::Hasher;
+use std::io;
+use std::thread;
+
+// appended to every input before hashing
+pub const STANDARD_SUFFIX: [u8; 5] = [17, 31, 73, 47, 23];
+
+// a twist longer than the list it's twisting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthError {
+    pub length: u8,
+    pub size: usize,
+}
+
+impl fmt::Display for LengthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "length {} is longer than the list of {}", self.length, self.size)
+    }
+}
+
+impl std::error::Error for LengthError {}
+
+// params that can't give a digest for some input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashError {
+    Length(LengthError),
+    // the list doesn't split into whole blocks
+    Blocks { size: usize, block_size: usize },
+    // a hasher takes any byte as a length
+    TooSmall { size: usize },
+}
+
+impl fmt::Display for HashError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HashError::Length(ref e) => e.fmt(f),
+            HashError::Blocks { size, block_size } => write!(f, "list size {} isn't a multiple of block size {}", size, block_size),
+            HashError::TooSmall { size } => write!(f, "a hasher needs a list of at least 256, not {}", size),
+        }
+    }
+}
+
+impl std::error::Error for HashError {}
+
+impl From<LengthError> for HashError {
+    fn from(e: LengthError) -> HashError {
+        HashError::Length(e)
+    }
+}
 
 pub struct State {
-    list: Vec<u8>,
+    list: Vec<u16>,
     skip_size: u32,
     pos: usize,
 }
 
 impl State {
+    // size can be at most 65536 so every value fits in a u16
     pub fn new(size: usize) -> State {
-        let mut arr = vec![0 as u8; size];
-        for i in 0..size {
-            arr[i] = i as u8;
-        }
+        assert!(size > 0 && size <= 65536, "list size {} must be between 1 and 65536", size);
+        let arr: Vec<u16> = (0..size).map(|i| i as u16).collect();
         State { list: arr, skip_size: 0, pos: 0 }
     }
 
-    pub fn twist(&mut self, length: u8) {
-        let mut tmp = vec![0; length as usize];
-        for i in 0..(length as usize) {
-            let j = (self.pos + i) % self.list.len();
-            tmp[i] = self.list[j];
+    // reverses the span in place, swapping from both ends
+    pub fn twist(&mut self, length: u8) -> Result<(), LengthError> {
+        let n = self.list.len();
+        let len = length as usize;
+        if len > n {
+            return Err(LengthError { length, size: n });
         }
-        tmp.reverse();
-        for i in 0..(length as usize) {
-            let j = (self.pos + i) % self.list.len();
-            self.list[j] = tmp[i];
+        if len > 1 {
+            // the two ends walk towards each other, wrapping
+            // without a division per swap
+            let (mut i, mut j) = (self.pos, (self.pos + len - 1) % n);
+            for _ in 0..(len / 2) {
+                self.list.swap(i, j);
+                i = if i + 1 == n { 0 } else { i + 1 };
+                j = if j == 0 { n - 1 } else { j - 1 };
+            }
         }
-        self.pos = self.pos + (length as usize) + (self.skip_size as usize);
+        self.pos = (self.pos + len + (self.skip_size as usize)) % n;
         self.skip_size += 1;
+
+        Ok(())
+    }
+
+    pub fn list(&self) -> &[u16] {
+        &self.list
     }
 
     pub fn value(&self) -> u32 {
@@ -36,35 +97,180 @@ impl State {
     }
 
     pub fn dense(&self) -> Vec<u8> {
+        self.dense_blocks(16)
+    }
+
+    // XOR of each block of the list. Lists longer than 256
+    // hold values past a byte; only the low byte is kept.
+    pub fn dense_blocks(&self, block_size: usize) -> Vec<u8> {
         let mut v = vec![];
-        let groups = self.list.len() / 16;
+        let groups = self.list.len() / block_size;
         for i in 0..groups {
-            let mut val = self.list[i*16];
-            let min = (i*16) + 1;
-            let max = (i+1) * 16;
+            let mut val = self.list[i*block_size];
+            let min = (i*block_size) + 1;
+            let max = (i+1) * block_size;
             for j in min..max {
-                val = val ^ self.list[j];
+                val ^= self.list[j];
             }
-            v.push(val);
+            v.push(val as u8);
         }
 
         v
     }
 }
 
-fn str_to_ascii(s: &str) -> Vec<u8> {
-    let mut v = vec![];
-    for b in s.trim().to_string().into_bytes() {
-       v.push(b); 
+// The standard 256 element list, kept rotated so the current
+// position is always index 0. Every twist is then a plain
+// slice reverse plus a rotate, with nothing allocated.
+#[derive(Clone)]
+pub struct State256 {
+    list: [u8; 256],
+    skip_size: usize,
+    // where list[0] really is
+    offset: usize,
+}
+
+impl State256 {
+    pub fn new() -> State256 {
+        let mut list = [0; 256];
+        for (i, x) in list.iter_mut().enumerate() {
+            *x = i as u8;
+        }
+        State256 { list, skip_size: 0, offset: 0 }
+    }
+
+    pub fn twist(&mut self, length: u8) {
+        self.list[..(length as usize)].reverse();
+        let step = ((length as usize) + self.skip_size) % 256;
+        self.list.rotate_left(step);
+        self.offset = (self.offset + step) % 256;
+        self.skip_size += 1;
+    }
+
+    // the list in its real order
+    pub fn list(&self) -> [u8; 256] {
+        let mut list = self.list;
+        list.rotate_right(self.offset);
+        list
+    }
+
+    pub fn value(&self) -> u32 {
+        let list = self.list();
+        (list[0] as u32) * (list[1] as u32)
     }
-    for b in vec![17, 31, 73, 47, 23] {
-        v.push(b);
+
+    pub fn dense(&self) -> [u8; 16] {
+        let mut digest = [0; 16];
+        for (i, block) in self.list().chunks(16).enumerate() {
+            digest[i] = block.iter().fold(0, |acc, x| acc ^ x);
+        }
+        digest
     }
+}
+
+impl Default for State256 {
+    fn default() -> State256 {
+        State256::new()
+    }
+}
+
+// the standard hash of input, suffix included
+pub fn hash_bytes(input: &[u8]) -> [u8; 16] {
+    let mut state = State256::new();
+    for _i in 0..64 {
+        for l in input.iter().chain(STANDARD_SUFFIX.iter()) {
+            state.twist(*l);
+        }
+    }
+
+    state.dense()
+}
+
+// hash_bytes for every input, split across threads
+pub fn hash_many<T: AsRef<[u8]> + Sync>(inputs: &[T]) -> Vec<[u8; 16]> {
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let chunk = inputs.len().div_ceil(threads).max(1);
+    let mut digests = vec![[0; 16]; inputs.len()];
+    thread::scope(|s| {
+        for (ins, outs) in inputs.chunks(chunk).zip(digests.chunks_mut(chunk)) {
+            s.spawn(move || {
+                for (i, o) in ins.iter().zip(outs.iter_mut()) {
+                    *o = hash_bytes(i.as_ref());
+                }
+            });
+        }
+    });
+
+    digests
+}
+
+// Bits of a digest, most significant first
+pub struct Bits<'a> {
+    bytes: &'a [u8],
+    next: usize,
+}
+
+impl<'a> Iterator for Bits<'a> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        if self.next >= self.bytes.len() * 8 {
+            return None;
+        }
+        let bit = self.bytes[self.next / 8] & (0x80 >> (self.next % 8)) != 0;
+        self.next += 1;
+        Some(bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.bytes.len() * 8 - self.next;
+        (left, Some(left))
+    }
+}
+
+impl<'a> ExactSizeIterator for Bits<'a> {}
+
+pub fn bits(bytes: &[u8]) -> Bits<'_> {
+    Bits { bytes, next: 0 }
+}
+
+// One 128 bit row per hash of "key-0", "key-1" and so on,
+// the first bit of each digest being the leftmost square
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    rows: Vec<u128>,
+}
+
+impl Bitmap {
+    pub const WIDTH: usize = 128;
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn rows(&self) -> &[u128] {
+        &self.rows
+    }
+
+    // false outside the bitmap
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < Bitmap::WIDTH && y < self.rows.len() && self.rows[y] & (1 << (127 - x)) != 0
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.rows.iter().map(|r| r.count_ones()).sum()
+    }
+}
+
+// the disk grid of day 14, hashed across threads
+pub fn grid(key: &str, rows: usize) -> Bitmap {
+    let keys: Vec<String> = (0..rows).map(|row| format!("{}-{}", key, row)).collect();
+    let rows = hash_many(&keys).iter().map(|d| u128::from_be_bytes(*d)).collect();
 
-    v
+    Bitmap { rows }
 }
 
-fn hex(bytes: &Vec<u8>) -> String {
+pub fn to_hex(bytes: &[u8]) -> String {
     let mut s = String::new();
     for b in bytes {
         write!(s, "{:02x}", b).expect("wtf");
@@ -72,20 +278,196 @@ fn hex(bytes: &Vec<u8>) -> String {
     s
 }
 
-pub fn hash_string(s: &str) -> String {
-    let lengths = str_to_ascii(s);
-    let mut state = State::new(256);
-    for _i in 0..64 {
-        for l in &lengths {
-            state.twist(*l);
+// Everything the standard hash hardcodes. Defaults give the
+// standard 16 byte hash; e.g.
+//
+//   KnotHashParams::new().size(512).suffix(b"salt")
+//
+// gives a 32 byte digest of a differently salted input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnotHashParams {
+    size: usize,
+    rounds: usize,
+    suffix: Vec<u8>,
+    block_size: usize,
+}
+
+impl Default for KnotHashParams {
+    fn default() -> KnotHashParams {
+        KnotHashParams { size: 256, rounds: 64, suffix: STANDARD_SUFFIX.to_vec(), block_size: 16 }
+    }
+}
+
+impl KnotHashParams {
+    pub fn new() -> KnotHashParams {
+        KnotHashParams::default()
+    }
+
+    // One round over the lengths as given, as in the first
+    // half of the puzzle. Use run() and look at the State.
+    pub fn sparse() -> KnotHashParams {
+        KnotHashParams::new().rounds(1).suffix(&[])
+    }
+
+    // list length; must be a multiple of the block size
+    pub fn size(mut self, size: usize) -> KnotHashParams {
+        assert!(size > 0 && size <= 65536, "list size {} must be between 1 and 65536", size);
+        self.size = size;
+        self
+    }
+
+    pub fn rounds(mut self, rounds: usize) -> KnotHashParams {
+        self.rounds = rounds;
+        self
+    }
+
+    pub fn suffix(mut self, suffix: &[u8]) -> KnotHashParams {
+        self.suffix = suffix.to_vec();
+        self
+    }
+
+    // list elements XORed into each digest byte
+    pub fn block_size(mut self, block_size: usize) -> KnotHashParams {
+        assert!(block_size > 0, "block size must be positive");
+        self.block_size = block_size;
+        self
+    }
+
+    pub fn digest_len(&self) -> usize {
+        self.size / self.block_size
+    }
+
+    // the sparse hash: every round of twists over input + suffix
+    pub fn run(&self, input: &[u8]) -> Result<State, LengthError> {
+        let mut lengths = input.to_vec();
+        lengths.extend_from_slice(&self.suffix);
+        let mut state = State::new(self.size);
+        for _i in 0..self.rounds {
+            for l in &lengths {
+                state.twist(*l)?;
+            }
+        }
+
+        Ok(state)
+    }
+
+    pub fn hash(&self, input: &[u8]) -> Result<Vec<u8>, HashError> {
+        if *self == KnotHashParams::default() {
+            return Ok(hash_bytes(input).to_vec());
+        }
+        if !self.size.is_multiple_of(self.block_size) {
+            return Err(HashError::Blocks { size: self.size, block_size: self.block_size });
+        }
+        Ok(self.run(input)?.dense_blocks(self.block_size))
+    }
+
+    // Any byte is a length to a hasher, so the list
+    // needs at least 256 elements
+    pub fn hasher(&self) -> Result<KnotHasher, HashError> {
+        if self.size < 256 {
+            return Err(HashError::TooSmall { size: self.size });
+        }
+        if !self.size.is_multiple_of(self.block_size) {
+            return Err(HashError::Blocks { size: self.size, block_size: self.block_size });
         }
+
+        Ok(KnotHasher { params: self.clone(), bytes: vec![] })
     }
-    hex(&state.dense())
+}
+
+// Knot hash over bytes fed in pieces. It isn't streaming:
+// every round replays the whole input, so update() buffers
+// everything given to it and memory grows with the input.
+// What it saves is the bytes having to be valid UTF-8 or in
+// one piece, and all 64 rounds wait for the digest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KnotHasher {
+    params: KnotHashParams,
+    bytes: Vec<u8>,
+}
+
+impl KnotHasher {
+    pub fn new() -> KnotHasher {
+        KnotHasher::default()
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    // digest of everything so far, of params.digest_len()
+    // bytes; more can still be added
+    pub fn digest(&self) -> Vec<u8> {
+        self.params.hash(&self.bytes).expect("hasher() checked the params")
+    }
+
+    // the standard 16 byte digest, or None if the params
+    // produce some other length; digest() gives those
+    pub fn finalize(&self) -> Option<[u8; 16]> {
+        let digest = self.digest();
+        if digest.len() != 16 {
+            return None;
+        }
+        let mut bytes = [0; 16];
+        bytes.copy_from_slice(&digest);
+
+        Some(bytes)
+    }
+
+    pub fn finalize_hex(&self) -> String {
+        to_hex(&self.digest())
+    }
+}
+
+// For HashMap<K, V, BuildHasherDefault<KnotHasher>>, when
+// the keys' knot hashes are what's wanted. This is slow: each
+// finish() runs all 64 rounds over everything written, and
+// every key buffers its bytes. Any other map is better off
+// with the default hasher.
+impl Hasher for KnotHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    // the first 8 digest bytes, big endian
+    fn finish(&self) -> u64 {
+        self.digest().iter().take(8).fold(0, |acc, b| (acc << 8) | (*b as u64))
+    }
+}
+
+// so io::copy can feed it a file or stdin
+impl io::Write for KnotHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// hex digest of s with surrounding whitespace trimmed
+pub fn hash_string(s: &str) -> String {
+    let mut hasher = KnotHasher::new();
+    hasher.update(s.trim().as_bytes());
+    hasher.finalize_hex()
 }
 
 #[cfg(test)]
 mod tests {
+    use std;
+    use std::collections::HashMap;
+    use std::hash::BuildHasherDefault;
+    use std::hash::Hasher;
     use State;
+    use KnotHasher;
+    use KnotHashParams;
+    use State256;
+    use hash_many;
+    use to_hex;
+    use bits;
+    use grid;
     use hash_string;
 
     #[test]
@@ -93,7 +475,7 @@ mod tests {
         let mut s = State::new(5);
         let lengths = vec![3, 4, 1, 5];
         for l in lengths {
-            s.twist(l);
+            s.twist(l).unwrap();
         }
         assert_eq!(12, s.value());
 
@@ -102,4 +484,87 @@ mod tests {
         assert_eq!("3efbe78a8d82f29979031a4aa0b16a9d".to_string(), hash_string("1,2,3"));
         assert_eq!("63960835bcdc130f0b66d7ff4f6a5a8e".to_string(), hash_string("1,2,4"));
     }
+
+    #[test]
+    fn hasher() {
+        let mut h = KnotHasher::new();
+        h.update(b"AoC");
+        h.update(b" 2017");
+        assert_eq!("33efeb34ea91902bb2f59c9920caa6cd", h.finalize_hex());
+        assert_eq!([0x33, 0xef, 0xeb, 0x34, 0xea, 0x91, 0x90, 0x2b], h.finalize().unwrap()[..8]);
+        assert_eq!(0x33efeb34ea91902b, h.finish());
+        assert_eq!("a2582a3a0e66e6e86e3812dcb672a272", KnotHasher::new().finalize_hex());
+
+        let mut copied = KnotHasher::new();
+        std::io::copy(&mut &b"1,2,3"[..], &mut copied).unwrap();
+        assert_eq!(hash_string("1,2,3"), copied.finalize_hex());
+
+        let mut map: HashMap<&str, u32, BuildHasherDefault<KnotHasher>> = HashMap::default();
+        map.insert("flqrgnkx", 8108);
+        map.insert("ffayrhll", 8190);
+        assert_eq!(Some(&8108), map.get("flqrgnkx"));
+    }
+
+    #[test]
+    fn params() {
+        assert_eq!(12, KnotHashParams::sparse().size(5).run(&[3, 4, 1, 5]).unwrap().value());
+        let standard = KnotHashParams::new();
+        assert_eq!(16, standard.digest_len());
+        let mut h = KnotHasher::new();
+        h.update(b"AoC 2017");
+        assert_eq!(h.finalize().unwrap().to_vec(), standard.hash(b"AoC 2017").unwrap());
+        let mut from_params = standard.hasher().unwrap();
+        from_params.update(b"AoC 2017");
+        assert_eq!(h, from_params);
+        let wide = KnotHashParams::new().size(512).suffix(b"salt");
+        assert_eq!(32, wide.digest_len());
+        assert_eq!(32, wide.hash(b"AoC 2017").unwrap().len());
+        assert_ne!(wide.hash(b"AoC 2017").unwrap()[..16], standard.hash(b"AoC 2017").unwrap()[..]);
+        let wide_blocks = KnotHashParams::new().block_size(8);
+        assert_eq!(32, wide_blocks.hasher().unwrap().digest().len());
+        assert_eq!(None, wide_blocks.hasher().unwrap().finalize());
+        // XORing pairs of 8 byte blocks gives back the 16 byte blocks
+        let halves = wide_blocks.hash(b"1,2,3").unwrap();
+        let folded: Vec<u8> = halves.chunks(2).map(|x| x[0] ^ x[1]).collect();
+        assert_eq!(standard.hash(b"1,2,3").unwrap(), folded);
+    }
+
+    #[test]
+    fn state256() {
+        let mut fast = State256::new();
+        let mut slow = State::new(256);
+        for l in b"flqrgnkx-0".iter().cycle().take(1000) {
+            fast.twist(*l);
+            slow.twist(*l).unwrap();
+        }
+        assert_eq!(slow.dense(), fast.dense().to_vec());
+        assert_eq!(slow.value(), fast.value());
+        // anything but the defaults takes the general path
+        let standard = KnotHashParams::new();
+        assert_eq!(standard.run(b"AoC 2017").unwrap().dense(), standard.hash(b"AoC 2017").unwrap());
+
+        let keys: Vec<String> = (0..20).map(|i| format!("flqrgnkx-{}", i)).collect();
+        let digests = hash_many(&keys);
+        for (k, d) in keys.iter().zip(digests.iter()) {
+            assert_eq!(hash_string(k), to_hex(d));
+        }
+        assert!(hash_many::<&str>(&[]).is_empty());
+    }
+
+    #[test]
+    fn bits_and_grid() {
+        assert_eq!(16, bits(&[0xa0, 0x01]).len());
+        let b: String = bits(&[0xa0, 0x01]).map(|x| if x { '1' } else { '0' }).collect();
+        assert_eq!("1010000000000001", b);
+
+        // the top corner of the puzzle's example, ##.#.#..
+        let disk = grid("flqrgnkx", 128);
+        assert_eq!(128, disk.height());
+        assert_eq!(8108, disk.count_ones());
+        let corner: Vec<bool> = (0..8).map(|x| disk.get(x, 0)).collect();
+        assert_eq!(vec![true, true, false, true, false, true, false, false], corner);
+        assert!(!disk.get(128, 0) && !disk.get(0, 128));
+        let row0: Vec<bool> = bits(&disk.rows()[0].to_be_bytes()).collect();
+        assert_eq!(corner, row0[..8].to_vec());
+    }
 }
@@ -0,0 +1,287 @@
+
+extern crate knot_hash;
+
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::PathBuf;
+use knot_hash::KnotHasher;
+use knot_hash::KnotHashParams;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Format {
+    Hex,
+    Raw,
+    Bits,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Options {
+    format: Format,
+    files: bool,
+    check: Option<PathBuf>,
+    sparse: Option<String>,
+    size: Option<usize>,
+    inputs: Vec<String>,
+}
+
+fn usage() -> String {
+    [
+        "Usage: knot-hash [--hex|--raw|--bits] [STRING...]",
+        "       knot-hash [--hex|--raw|--bits] --files [FILE...]",
+        "       knot-hash --check LIST [--files]",
+        "       knot-hash --sparse LENGTHS [--size N]",
+        "",
+        "Hashes each STRING, or each line of stdin if there are none,",
+        "trimmed of surrounding whitespace as day 10 trims its input.",
+        "With --files the whole of each FILE is hashed byte for byte,",
+        "- being stdin.",
+        "--check reads \"DIGEST  INPUT\" lines and reports OK or FAILED.",
+        "--sparse prints the product of the first two list values after",
+        "one round of the comma separated LENGTHS over a list of N",
+        "(default 256).",
+    ].join("\n")
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut opts = Options { format: Format::Hex, files: false, check: None, sparse: None, size: None, inputs: vec![] };
+    // the format flag given, if any
+    let mut format = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--hex" => opts.format = Format::Hex,
+            "--raw" => opts.format = Format::Raw,
+            "--bits" => opts.format = Format::Bits,
+            "--files" | "-f" => opts.files = true,
+            "--check" | "-c" => {
+                i += 1;
+                match args.get(i) {
+                    Some(list) => opts.check = Some(PathBuf::from(list)),
+                    None => return Err("--check needs a list of digests".to_string()),
+                }
+            },
+            "--sparse" => {
+                i += 1;
+                match args.get(i) {
+                    Some(lengths) => opts.sparse = Some(lengths.clone()),
+                    None => return Err("--sparse needs comma separated lengths".to_string()),
+                }
+            },
+            "--size" => {
+                i += 1;
+                match args.get(i).map(|x| x.parse()) {
+                    Some(Ok(n)) if n > 0 && n <= 65536 => opts.size = Some(n),
+                    _ => return Err("--size must be between 1 and 65536".to_string()),
+                }
+            },
+            "--help" | "-h" => return Err(usage()),
+            "--" => {
+                opts.inputs.extend(args[(i + 1)..].iter().cloned());
+                break;
+            },
+            x if x.starts_with("--") => return Err(format!("Unexpected argument: {}\n{}", x, usage())),
+            x => opts.inputs.push(x.to_string()),
+        }
+        if let "--hex" | "--raw" | "--bits" = args[i].as_str() {
+            format = Some(args[i].clone());
+        }
+        i += 1;
+    }
+
+    if (opts.check.is_some() || opts.sparse.is_some()) && !opts.inputs.is_empty() {
+        return Err(format!("--check and --sparse don't take inputs\n{}", usage()));
+    }
+    if let (Some(f), true) = (format, opts.check.is_some() || opts.sparse.is_some()) {
+        return Err(format!("{} doesn't go with --check or --sparse\n{}", f, usage()));
+    }
+    if opts.files && opts.sparse.is_some() {
+        return Err(format!("--files doesn't go with --sparse\n{}", usage()));
+    }
+    if opts.size.is_some() && opts.sparse.is_none() {
+        return Err(format!("--size only goes with --sparse\n{}", usage()));
+    }
+
+    Ok(opts)
+}
+
+fn parse_lengths(s: &str) -> Result<Vec<u8>, String> {
+    s.split(',').map(|x| x.trim().parse().map_err(|_e| format!("Bad length: {}", x))).collect()
+}
+
+fn to_bits(digest: &[u8]) -> String {
+    knot_hash::bits(digest).map(|b| if b { '1' } else { '0' }).collect()
+}
+
+// a whole file, or stdin for -
+fn hash_file(path: &str) -> io::Result<[u8; 16]> {
+    let mut hasher = KnotHasher::new();
+    if path == "-" {
+        io::copy(&mut io::stdin().lock(), &mut hasher)?;
+    } else {
+        io::copy(&mut File::open(path)?, &mut hasher)?;
+    }
+
+    Ok(hasher.finalize().expect("the default hasher gives 16 bytes"))
+}
+
+// the same digest as hash_string, which trims
+fn hash_str(s: &str) -> [u8; 16] {
+    knot_hash::hash_bytes(s.trim().as_bytes())
+}
+
+fn hash_input(opts: &Options, input: &str) -> Result<[u8; 16], String> {
+    if opts.files {
+        hash_file(input).map_err(|e| format!("{}: {}", input, e))
+    } else {
+        Ok(hash_str(input))
+    }
+}
+
+fn print(out: &mut dyn Write, format: Format, digest: &[u8; 16], name: Option<&str>) -> io::Result<()> {
+    match format {
+        Format::Raw => return out.write_all(digest),
+        Format::Hex => write!(out, "{}", knot_hash::to_hex(digest))?,
+        Format::Bits => write!(out, "{}", to_bits(digest))?,
+    }
+    match name {
+        Some(n) => writeln!(out, "  {}", n),
+        None => writeln!(out),
+    }
+}
+
+// "DIGEST  INPUT" per line, as printed for files
+fn check(opts: &Options, list: &PathBuf) -> Result<bool, String> {
+    let file = File::open(list).map_err(|e| format!("{}: {}", list.display(), e))?;
+    let (mut ok, mut failed, mut bad) = (0, 0, 0);
+    for line in io::BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("{}: {}", list.display(), e))?;
+        if line.is_empty() {
+            continue;
+        }
+        let (expected, input) = match line.find("  ") {
+            Some(i) => (&line[..i], &line[(i + 2)..]),
+            None => {
+                bad += 1;
+                continue;
+            },
+        };
+        match hash_input(opts, input) {
+            Ok(d) if knot_hash::to_hex(&d) == expected.to_lowercase() => {
+                ok += 1;
+                println!("{}: OK", input);
+            },
+            Ok(_) => {
+                failed += 1;
+                println!("{}: FAILED", input);
+            },
+            Err(e) => {
+                failed += 1;
+                eprintln!("{}", e);
+                println!("{}: FAILED open or read", input);
+            },
+        }
+    }
+    if bad > 0 {
+        eprintln!("knot-hash: WARNING: {} line(s) are improperly formatted", bad);
+    }
+    if failed > 0 {
+        eprintln!("knot-hash: WARNING: {} of {} computed checksum(s) did NOT match", failed, ok + failed);
+    }
+
+    Ok(failed == 0 && bad == 0 && ok > 0)
+}
+
+fn run(opts: &Options) -> Result<bool, String> {
+    if let Some(ref lengths) = opts.sparse {
+        let state = KnotHashParams::sparse().size(opts.size.unwrap_or(256)).run(&parse_lengths(lengths)?).map_err(|e| e.to_string())?;
+        println!("{}", state.value());
+        return Ok(true);
+    }
+    if let Some(ref list) = opts.check {
+        return check(opts, list);
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut ok = true;
+    let write_err = |e: io::Error| e.to_string();
+    if opts.inputs.is_empty() && !opts.files {
+        for line in io::stdin().lock().lines() {
+            let line = line.map_err(write_err)?;
+            print(&mut out, opts.format, &hash_str(&line), None).map_err(write_err)?;
+        }
+    } else if opts.files {
+        let stdin = vec!["-".to_string()];
+        let inputs = if opts.inputs.is_empty() { &stdin } else { &opts.inputs };
+        for path in inputs {
+            match hash_input(opts, path) {
+                Ok(d) => print(&mut out, opts.format, &d, Some(path)).map_err(write_err)?,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    ok = false;
+                },
+            }
+        }
+    } else {
+        for s in &opts.inputs {
+            print(&mut out, opts.format, &hash_str(s), None).map_err(write_err)?;
+        }
+    }
+
+    Ok(ok)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let opts = match parse_args(&args) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        },
+    };
+    match run(&opts) {
+        Ok(true) => (),
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("knot-hash: {}", e);
+            std::process::exit(1);
+        },
+    }
+}
+
+#[test]
+fn test() {
+    let args = |s: &str| -> Vec<String> { s.split(' ').filter(|x| !x.is_empty()).map(|x| x.to_string()).collect() };
+    let a = parse_args(&args("--bits AoC 2017")).unwrap();
+    assert_eq!(Format::Bits, a.format);
+    assert_eq!(vec!["AoC", "2017"], a.inputs);
+    let b = parse_args(&args("--files -c sums.txt")).unwrap();
+    assert!(b.files);
+    assert_eq!(Some(PathBuf::from("sums.txt")), b.check);
+    let c = parse_args(&args("--sparse 3,4,1,5 --size 5")).unwrap();
+    assert_eq!((Some("3,4,1,5".to_string()), Some(5)), (c.sparse, c.size));
+    assert!(parse_args(&args("--sparse 3 --size 0")).is_err());
+    assert!(parse_args(&args("--size 5 AoC")).is_err());
+    assert!(parse_args(&args("--raw --check sums.txt")).unwrap_err().starts_with("--raw doesn't go with --check or --sparse\n"));
+    assert!(parse_args(&args("--sparse 3,4 --bits")).is_err());
+    assert!(parse_args(&args("--hex --sparse 3,4")).is_err());
+    assert!(parse_args(&args("--sparse 3,4 --files")).is_err());
+    assert_eq!(vec!["--raw"], parse_args(&args("-- --raw")).unwrap().inputs);
+    assert!(parse_args(&args("--check")).is_err());
+    assert!(parse_args(&args("--sparse 1,2 extra")).is_err());
+    assert!(parse_args(&args("--nope")).is_err());
+
+    assert_eq!(Ok(vec![3, 4, 1, 5]), parse_lengths("3, 4,1,5"));
+    assert!(parse_lengths("3,256").is_err());
+    assert_eq!("0000000111111111", to_bits(&[1, 255]));
+    assert_eq!(knot_hash::hash_string("1,2,3\n"), knot_hash::to_hex(&hash_str("1,2,3\n")));
+
+    let mut out = vec![];
+    let digest = knot_hash::hash_bytes(b"1,2,3");
+    print(&mut out, Format::Hex, &digest, Some("x.txt")).unwrap();
+    print(&mut out, Format::Hex, &digest, None).unwrap();
+    assert_eq!("3efbe78a8d82f29979031a4aa0b16a9d  x.txt\n3efbe78a8d82f29979031a4aa0b16a9d\n", String::from_utf8(out).unwrap());
+}
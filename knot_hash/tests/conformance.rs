@@ -0,0 +1,167 @@
+// Known vectors from the puzzle text, invariants of the twist,
+// and randomized checks that every implementation agrees with
+// a straight transcription of the puzzle.
+
+extern crate knot_hash;
+
+use knot_hash::HashError;
+use knot_hash::KnotHasher;
+use knot_hash::KnotHashParams;
+use knot_hash::LengthError;
+use knot_hash::State;
+use knot_hash::State256;
+
+// xorshift, so failures reproduce without pulling in rand
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % (n as u64)) as usize
+    }
+
+    fn bytes(&mut self, max_len: usize) -> Vec<u8> {
+        let len = self.below(max_len + 1);
+        (0..len).map(|_i| self.next() as u8).collect()
+    }
+}
+
+// the puzzle's description, copied out and reversed each time
+fn reference(input: &[u8]) -> String {
+    let mut list: Vec<u8> = (0..=255).collect();
+    let (mut pos, mut skip) = (0, 0);
+    let lengths: Vec<usize> = input.iter().chain([17, 31, 73, 47, 23].iter()).map(|x| *x as usize).collect();
+    for _round in 0..64 {
+        for len in &lengths {
+            let span: Vec<u8> = (0..*len).map(|i| list[(pos + i) % 256]).rev().collect();
+            for (i, v) in span.into_iter().enumerate() {
+                list[(pos + i) % 256] = v;
+            }
+            pos += len + skip;
+            skip += 1;
+        }
+    }
+
+    list.chunks(16).map(|b| format!("{:02x}", b.iter().fold(0, |acc, x| acc ^ x))).collect()
+}
+
+#[test]
+fn published_vectors() {
+    let vectors = [
+        ("", "a2582a3a0e66e6e86e3812dcb672a272"),
+        ("AoC 2017", "33efeb34ea91902bb2f59c9920caa6cd"),
+        ("1,2,3", "3efbe78a8d82f29979031a4aa0b16a9d"),
+        ("1,2,4", "63960835bcdc130f0b66d7ff4f6a5a8e"),
+    ];
+    for &(input, digest) in vectors.iter() {
+        assert_eq!(digest, knot_hash::hash_string(input), "{:?}", input);
+        assert_eq!(digest, knot_hash::to_hex(&knot_hash::hash_bytes(input.as_bytes())), "{:?}", input);
+        assert_eq!(digest, reference(input.as_bytes()), "{:?}", input);
+    }
+
+    let sparse = KnotHashParams::sparse().size(5).run(&[3, 4, 1, 5]).unwrap();
+    assert_eq!(&[3, 4, 2, 1, 0], sparse.list());
+    assert_eq!(12, sparse.value());
+
+    // the corner of the day 14 example
+    let corner = [
+        "##.#.#..",
+        ".#.#.#.#",
+        "....#.#.",
+        "#.#.##.#",
+        ".##.#...",
+        "##..#..#",
+        ".#...#..",
+        "##.#.##.",
+    ];
+    let disk = knot_hash::grid("flqrgnkx", 8);
+    for (y, row) in corner.iter().enumerate() {
+        let got: String = (0..8).map(|x| if disk.get(x, y) { '#' } else { '.' }).collect();
+        assert_eq!(*row, got);
+    }
+    assert_eq!(8108, knot_hash::grid("flqrgnkx", 128).count_ones());
+}
+
+#[test]
+fn invariants() {
+    let mut rng = Rng(0x2017);
+    for size in [1, 2, 5, 16, 255, 256, 300].iter() {
+        let mut state = State::new(*size);
+        for _i in 0..50 {
+            let len = rng.below((*size).min(255) + 1) as u8;
+            state.twist(len).unwrap();
+        }
+        let before = state.list().to_vec();
+        state.twist(0).unwrap();
+        assert_eq!(before, state.list(), "length 0 on {}", size);
+        state.twist(1).unwrap();
+        assert_eq!(before, state.list(), "length 1 on {}", size);
+
+        // still a permutation
+        let mut sorted = before.clone();
+        sorted.sort();
+        assert_eq!((0..*size as u16).collect::<Vec<_>>(), sorted);
+    }
+
+    // the whole list may be reversed, but no more
+    let mut small = State::new(5);
+    assert_eq!(Ok(()), small.twist(5));
+    assert_eq!(&[4, 3, 2, 1, 0], small.list());
+    assert_eq!(Err(LengthError { length: 6, size: 5 }), small.twist(6));
+    assert_eq!(Err(LengthError { length: 255, size: 5 }), State::new(5).twist(255));
+    assert_eq!("length 6 is longer than the list of 5", LengthError { length: 6, size: 5 }.to_string());
+    assert!(KnotHashParams::sparse().size(5).run(&[3, 4, 1, 9]).is_err());
+    assert!(KnotHashParams::new().size(16).hash(b"AoC 2017").is_err());
+    assert_eq!(Err(HashError::Blocks { size: 300, block_size: 16 }), KnotHashParams::new().size(300).hash(b"1,2,3"));
+    assert_eq!(Some(HashError::TooSmall { size: 16 }), KnotHashParams::new().size(16).hasher().err());
+    assert_eq!(Some(HashError::Blocks { size: 300, block_size: 16 }), KnotHashParams::new().size(300).hasher().err());
+}
+
+#[test]
+fn randomized_agreement() {
+    let mut rng = Rng(0xdecade);
+    let general = KnotHashParams::new().block_size(16).rounds(64);
+    for _case in 0..200 {
+        let input = rng.bytes(40);
+        let expected = reference(&input);
+
+        assert_eq!(expected, knot_hash::to_hex(&knot_hash::hash_bytes(&input)));
+        let slow = general.run(&input).unwrap().dense();
+        assert_eq!(expected, knot_hash::to_hex(&slow));
+
+        // however the bytes are split up
+        let mut hasher = KnotHasher::new();
+        let mut rest = &input[..];
+        while !rest.is_empty() {
+            let (a, b) = rest.split_at(rng.below(rest.len()) + 1);
+            hasher.update(a);
+            rest = b;
+        }
+        assert_eq!(expected, hasher.finalize_hex());
+    }
+
+    for _case in 0..200 {
+        let lengths = rng.bytes(100);
+        let mut fast = State256::new();
+        let mut slow = State::new(256);
+        for l in &lengths {
+            fast.twist(*l);
+            slow.twist(*l).unwrap();
+        }
+        let fast_list: Vec<u16> = fast.list().iter().map(|x| *x as u16).collect();
+        assert_eq!(slow.list(), &fast_list[..]);
+        assert_eq!(slow.value(), fast.value());
+    }
+
+    let inputs: Vec<Vec<u8>> = (0..50).map(|_i| rng.bytes(30)).collect();
+    let many = knot_hash::hash_many(&inputs);
+    for (input, digest) in inputs.iter().zip(many.iter()) {
+        assert_eq!(knot_hash::hash_bytes(input), *digest);
+    }
+}
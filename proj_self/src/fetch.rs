@@ -0,0 +1,282 @@
+
+use std::env;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpStream;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+
+use Error;
+
+pub const BASE_URL: &str = "https://adventofcode.com/2017";
+
+const USER_AGENT: &str = "github.com/gohanman/AdventOfCode2017 input fetcher";
+
+// How a page actually gets downloaded. Tests and
+// offline setups can swap in their own.
+pub trait Backend {
+    fn get(&self, url: &str, session: &str) -> Result<String, Error>;
+}
+
+// Shells out to curl, which takes care of https
+pub struct Curl;
+
+// The session, trimmed. A newline in it would start another
+// curl config line or another request header, so control
+// characters and whitespace are refused.
+fn check_session<'a>(url: &str, session: &'a str) -> Result<&'a str, Error> {
+    let session = session.trim();
+    if session.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        return Err(Error::Fetch(url.to_string(), "the session token has whitespace or control characters in it".to_string()));
+    }
+
+    Ok(session)
+}
+
+// The cookie goes to curl as a config file on stdin; on the
+// command line anyone could read it from ps.
+fn curl_config(session: &str) -> String {
+    format!("cookie = \"session={}\"\n", session.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Backend for Curl {
+    fn get(&self, url: &str, session: &str) -> Result<String, Error> {
+        let fail = |why: String| Error::Fetch(url.to_string(), why);
+        let session = check_session(url, session)?;
+        let mut child = process::Command::new("curl")
+            .arg("--silent")
+            .arg("--show-error")
+            .arg("--fail")
+            .arg("--user-agent").arg(USER_AGENT)
+            .arg("--config").arg("-")
+            .arg(url)
+            .stdin(process::Stdio::piped())
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::piped())
+            .spawn()
+            .map_err(|e| fail(format!("could not run curl: {}", e)))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(curl_config(session).as_bytes()).map_err(|e| fail(format!("could not run curl: {}", e)))?;
+        }
+        let out = child.wait_with_output().map_err(|e| fail(format!("could not run curl: {}", e)))?;
+        if !out.status.success() {
+            let why = String::from_utf8_lossy(&out.stderr).trim().to_string();
+            return Err(fail(why));
+        }
+
+        String::from_utf8(out.stdout).map_err(|_e| fail("response is not UTF-8".to_string()))
+    }
+}
+
+// Bare bones HTTP/1.0 client for plain http:// urls,
+// e.g. a local mirror or a stub server
+pub struct Http;
+
+// split http://host:port/path into (host:port, /path)
+fn split_url(url: &str) -> Option<(String, String)> {
+    let rest = url.trim_start_matches("http://");
+    if rest.len() == url.len() {
+        return None;
+    }
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let addr = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+
+    Some((addr, path.to_string()))
+}
+
+impl Backend for Http {
+    fn get(&self, url: &str, session: &str) -> Result<String, Error> {
+        let fail = |why: String| Error::Fetch(url.to_string(), why);
+        let session = check_session(url, session)?;
+        let (addr, path) = split_url(url).ok_or_else(|| fail("only http:// urls are supported".to_string()))?;
+        let mut stream = TcpStream::connect(&addr).map_err(|e| fail(e.to_string()))?;
+        let request = format!(
+            "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: {}\r\nCookie: session={}\r\n\r\n",
+            path, addr, USER_AGENT, session
+        );
+        stream.write_all(request.as_bytes()).map_err(|e| fail(e.to_string()))?;
+        let mut response = vec![];
+        stream.read_to_end(&mut response).map_err(|e| fail(e.to_string()))?;
+        let response = String::from_utf8(response).map_err(|_e| fail("response is not UTF-8".to_string()))?;
+
+        let (head, body) = match response.find("\r\n\r\n") {
+            Some(i) => (&response[..i], &response[(i + 4)..]),
+            None => return Err(fail("malformed response".to_string())),
+        };
+        let status = head.lines().next().unwrap_or("");
+        if status.split_whitespace().nth(1) != Some("200") {
+            return Err(fail(status.to_string()));
+        }
+
+        Ok(body.to_string())
+    }
+}
+
+// Downloads puzzle inputs into a cache directory laid out like
+// the repo, [cache]/day07/input.txt, so the cache can also be
+// handed to a Resolver. Anything already cached is never
+// fetched again.
+pub struct Fetcher {
+    backend: Box<dyn Backend>,
+    base_url: String,
+    cache_dir: PathBuf,
+    session: Option<String>,
+}
+
+impl Fetcher {
+    pub fn new(backend: Box<dyn Backend>, base_url: &str, cache_dir: PathBuf, session: Option<String>) -> Fetcher {
+        Fetcher {
+            backend,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            cache_dir,
+            session,
+        }
+    }
+
+    // Configured from the environment: AOC_BASE_URL,
+    // AOC_CACHE_DIR (default ~/.cache/aoc2017) and AOC_SESSION.
+    // Plain http:// base urls skip curl.
+    pub fn from_env() -> Fetcher {
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_e| BASE_URL.to_string());
+        let backend: Box<dyn Backend> = if base_url.starts_with("http://") { Box::new(Http) } else { Box::new(Curl) };
+
+        Fetcher::new(backend, &base_url, default_cache_dir(), env::var("AOC_SESSION").ok())
+    }
+
+    pub fn cache_dir(&self) -> &PathBuf {
+        &self.cache_dir
+    }
+
+    pub fn cache_path(&self, day: u32) -> PathBuf {
+        self.cache_dir.join(format!("day{:02}", day)).join("input.txt")
+    }
+
+    pub fn url(&self, day: u32) -> String {
+        format!("{}/day/{}/input", self.base_url, day)
+    }
+
+    // the cached input, fetching it first if need be
+    pub fn input(&self, day: u32) -> Result<String, Error> {
+        let path = self.cache_path(day);
+        if path.is_file() {
+            return ::try_file_to_str(&path);
+        }
+        let session = match self.session {
+            Some(ref s) => s,
+            None => return Err(Error::NoSession),
+        };
+        let body = self.backend.get(&self.url(day), session)?;
+
+        let dir = path.parent().unwrap().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| Error::Unwritable(dir, e))?;
+        write_atomically(&path, &body).map_err(|e| Error::Unwritable(path.clone(), e))?;
+
+        Ok(body)
+    }
+}
+
+// Through a temporary file in the same directory, so an
+// interrupted write never leaves a truncated input that
+// would look cached.
+fn write_atomically(path: &Path, body: &str) -> io::Result<()> {
+    let tmp = path.with_extension(format!("tmp{}", process::id()));
+    fs::write(&tmp, body).and_then(|_| fs::rename(&tmp, path)).inspect_err(|_e| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+fn default_cache_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("AOC_CACHE_DIR") {
+        return PathBuf::from(dir);
+    }
+    let home = env::var_os("HOME").map(PathBuf::from).unwrap_or_else(env::temp_dir);
+
+    home.join(".cache").join("aoc2017")
+}
+
+#[cfg(test)]
+mod tests {
+    use std;
+    use std::io::Read;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::thread;
+    use fetch::Backend;
+    use fetch::Curl;
+    use fetch::curl_config;
+    use fetch::Fetcher;
+    use fetch::Http;
+    use fetch::split_url;
+    use Error;
+
+    // answers exactly one request, then goes away
+    fn stub_server(body: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}/2017", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut conn, _addr) = listener.accept().unwrap();
+            let mut request = vec![0; 1024];
+            let len = conn.read(&mut request).unwrap();
+            let response = format!("HTTP/1.0 200 OK\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+            conn.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&request[..len]).to_string()
+        });
+
+        (base, handle)
+    }
+
+    #[test]
+    fn it_works() {
+        assert_eq!(Some(("localhost:8080".to_string(), "/day/1/input".to_string())), split_url("http://localhost:8080/day/1/input"));
+        assert_eq!(Some(("example.com:80".to_string(), "/".to_string())), split_url("http://example.com"));
+        assert_eq!(None, split_url("https://example.com/"));
+        assert_eq!("cookie = \"session=ab\\\"c\"\n", curl_config("ab\"c"));
+
+        let cache = std::env::temp_dir().join(format!("proj_self_fetch_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache);
+        let (base, server) = stub_server("1122\n");
+        let fetcher = Fetcher::new(Box::new(Http), &base, cache.clone(), Some("abc123".to_string()));
+        assert_eq!("1122\n", fetcher.input(1).unwrap());
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2017/day/1/input HTTP/1.0\r\n"), "{}", request);
+        assert!(request.contains("Cookie: session=abc123\r\n"), "{}", request);
+        assert!(fetcher.cache_path(1).is_file());
+        assert_eq!(vec!["input.txt"], std::fs::read_dir(cache.join("day01")).unwrap().map(|e| e.unwrap().file_name().into_string().unwrap()).collect::<Vec<String>>());
+
+        // the stub is gone, so this has to come from the cache
+        assert_eq!("1122\n", fetcher.input(1).unwrap());
+
+        let anonymous = Fetcher::new(Box::new(Http), &base, cache.clone(), None);
+        match anonymous.input(2) {
+            Err(Error::NoSession) => (),
+            x => panic!("expected NoSession, got {:?}", x),
+        }
+        let _ = std::fs::remove_dir_all(&cache);
+    }
+
+    #[test]
+    fn bad_sessions() {
+        // refused before curl runs or anything connects
+        for session in ["abc\nurl = http://evil", "abc\r\nX-Evil: 1"].iter() {
+            match Curl.get("https://example.com/", session) {
+                Err(Error::Fetch(_, ref why)) if why.contains("session") => (),
+                x => panic!("expected a session error, got {:?}", x),
+            }
+            match Http.get("http://127.0.0.1:9/", session) {
+                Err(Error::Fetch(_, ref why)) if why.contains("session") => (),
+                x => panic!("expected a session error, got {:?}", x),
+            }
+        }
+
+        let (base, server) = stub_server("1\n");
+        assert_eq!("1\n", Http.get(&format!("{}/day/1/input", base), " abc123\n").unwrap());
+        assert!(server.join().unwrap().contains("Cookie: session=abc123\r\n"));
+    }
+}
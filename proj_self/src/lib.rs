@@ -1,31 +1,157 @@
 
+use std::env;
+use std::fmt;
+use std::io;
 use std::path::PathBuf;
 use std::io::Read;
 
-// remove [depth] entries from the path to the current
-// executable and return the resulting path
-pub fn proj_dir(depth: u32) -> PathBuf {
+pub mod fetch;
+
+// everything that can go wrong finding or reading an input file
+#[derive(Debug)]
+pub enum Error {
+    MissingFile(PathBuf),
+    Unreadable(PathBuf, io::Error),
+    NotUtf8(PathBuf),
+    TooDeep(PathBuf, u32),
+    NoExecutable(io::Error),
+    NotFound(Vec<(String, PathBuf)>),
+    Unwritable(PathBuf, io::Error),
+    NoSession,
+    Fetch(String, String),
+}
 
-    fn rm_dirs(num: u32, path: PathBuf) -> PathBuf {
-        if num == 0 {
-            return path;
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::MissingFile(ref p) => write!(f, "input file {} does not exist", p.display()),
+            Error::Unreadable(ref p, ref e) => write!(f, "could not read {}: {}", p.display(), e),
+            Error::NotUtf8(ref p) => write!(f, "{} is not valid UTF-8", p.display()),
+            Error::TooDeep(ref p, depth) => write!(f, "cannot go up {} directories from {}", depth, p.display()),
+            Error::NoExecutable(ref e) => write!(f, "could not locate the running executable: {}", e),
+            Error::NotFound(ref tried) => {
+                write!(f, "no input file found, tried:")?;
+                for (source, p) in tried {
+                    write!(f, "\n  {} (from {})", p.display(), source)?;
+                }
+                Ok(())
+            },
+            Error::Unwritable(ref p, ref e) => write!(f, "could not write {}: {}", p.display(), e),
+            Error::NoSession => write!(f, "no session token, set AOC_SESSION to fetch inputs"),
+            Error::Fetch(ref url, ref why) => write!(f, "could not fetch {}: {}", url, why),
         }
+    }
+}
+
+impl std::error::Error for Error {}
 
-        let parent = path.parent().unwrap().to_path_buf();
-        rm_dirs(num - 1, parent)
+// Finds a day's input file. Each base directory is expected
+// to hold one sub directory per day, e.g. [base]/day07/input.txt,
+// and the bases are searched in the order they were added.
+#[derive(Debug, Clone, Default)]
+pub struct Resolver {
+    bases: Vec<(String, PathBuf)>,
+}
+
+impl Resolver {
+    pub fn new() -> Resolver {
+        Resolver { bases: vec![] }
     }
 
-    let exe = std::env::current_exe().unwrap();
-    rm_dirs(depth, exe)
+    // The standard search order: an explicit --input directory,
+    // $AOC_INPUT_DIR, the workspace the binary was built from
+    // (pass the CARGO_MANIFEST_DIR of a crate in the workspace root)
+    // and finally the current directory
+    pub fn standard(explicit: Option<PathBuf>, manifest_dir: &str) -> Resolver {
+        let mut r = Resolver::new();
+        if let Some(dir) = explicit {
+            r.add("--input", dir);
+        }
+        if let Some(dir) = env::var_os("AOC_INPUT_DIR") {
+            r.add("AOC_INPUT_DIR", PathBuf::from(dir));
+        }
+        if let Some(root) = PathBuf::from(manifest_dir).parent() {
+            r.add("CARGO_MANIFEST_DIR", root.to_path_buf());
+        }
+        if let Ok(cwd) = env::current_dir() {
+            r.add("current directory", cwd);
+        }
+
+        r
+    }
+
+    pub fn add(&mut self, source: &str, base: PathBuf) {
+        self.bases.push((source.to_string(), base));
+    }
+
+    // first existing [base]/[day]/[file]
+    pub fn find(&self, day: &str, file: &str) -> Result<PathBuf, Error> {
+        let mut tried = vec![];
+        for (source, base) in &self.bases {
+            let path = base.join(day).join(file);
+            if path.is_file() {
+                return Ok(path);
+            }
+            tried.push((source.clone(), path));
+        }
+
+        Err(Error::NotFound(tried))
+    }
+}
+
+// remove [depth] entries from the path to the current
+// executable and return the resulting path
+pub fn try_proj_dir(depth: u32) -> Result<PathBuf, Error> {
+    let exe = std::env::current_exe().map_err(Error::NoExecutable)?;
+    let mut path = exe.clone();
+    for _i in 0..depth {
+        path = match path.parent() {
+            Some(p) => p.to_path_buf(),
+            None => return Err(Error::TooDeep(exe, depth)),
+        };
+    }
+
+    Ok(path)
+}
+
+// panicking version of try_proj_dir
+pub fn proj_dir(depth: u32) -> PathBuf {
+    try_proj_dir(depth).unwrap_or_else(|e| panic!("{}", e))
 }
 
 // read a file into a string and return it
+pub fn try_file_to_str(file: &PathBuf) -> Result<String, Error> {
+    let mut f = std::fs::File::open(file).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => Error::MissingFile(file.clone()),
+        _ => Error::Unreadable(file.clone(), e),
+    })?;
+    let mut bytes = vec![];
+    f.read_to_end(&mut bytes).map_err(|e| Error::Unreadable(file.clone(), e))?;
+
+    String::from_utf8(bytes).map_err(|_e| Error::NotUtf8(file.clone()))
+}
+
+// read standard input into a string and return it
+pub fn try_stdin_to_str() -> Result<String, Error> {
+    let stdin = PathBuf::from("-");
+    let mut bytes = vec![];
+    io::stdin().read_to_end(&mut bytes).map_err(|e| Error::Unreadable(stdin.clone(), e))?;
+
+    String::from_utf8(bytes).map_err(|_e| Error::NotUtf8(stdin))
+}
+
+// read a file, or standard input if the path is "-"
+pub fn try_read_input(file: &PathBuf) -> Result<String, Error> {
+    if file.as_os_str() == "-" {
+        try_stdin_to_str()
+    } else {
+        try_file_to_str(file)
+    }
+}
+
+// panicking version of try_file_to_str
 pub fn file_to_str(file: &PathBuf) -> String {
-    let mut input = String::new();
-    let _io = std::fs::File::open(file).unwrap().read_to_string(
-        &mut input,
-    );
-    input
+    try_file_to_str(file).unwrap_or_else(|e| panic!("{}", e))
 }
 
 // split a string into lines
@@ -33,7 +159,7 @@ pub fn str_to_lines(input: &str) -> Vec<&str> {
     input
         .split("\n")
         .map(|x| x.trim())
-        .filter(|x| x.len() > 0)
+        .filter(|x| !x.is_empty())
         .collect()
 }
 
@@ -42,6 +168,10 @@ mod tests {
     use std;
     use proj_dir;
     use file_to_str;
+    use try_proj_dir;
+    use try_file_to_str;
+    use Error;
+    use Resolver;
 
     #[test]
     fn it_works() {
@@ -53,10 +183,63 @@ mod tests {
         let my_parent2 = proj_dir(1);
         assert_eq!(my_parent, my_parent2);
 
-        let mut path = proj_dir(4);
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         path.push("src");
         path.push("lib.rs");
         let my_code = file_to_str(&path);
-        assert!(my_code.len() > 0, "Couldn't find myself");
+        assert!(!my_code.is_empty(), "Couldn't find myself");
+    }
+
+    #[test]
+    fn errors() {
+        match try_proj_dir(1000) {
+            Err(Error::TooDeep(_, 1000)) => (),
+            x => panic!("expected TooDeep, got {:?}", x),
+        }
+
+        let missing = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("no_such_file.txt");
+        match try_file_to_str(&missing) {
+            Err(Error::MissingFile(ref p)) => assert_eq!(*p, missing),
+            x => panic!("expected MissingFile, got {:?}", x),
+        }
+
+        let binary = std::env::temp_dir().join("proj_self_not_utf8.bin");
+        std::fs::write(&binary, [0xff, 0xfe, 0xfd]).unwrap();
+        match try_file_to_str(&binary) {
+            Err(Error::NotUtf8(_)) => (),
+            x => panic!("expected NotUtf8, got {:?}", x),
+        }
+        let _ = std::fs::remove_file(&binary);
+
+        let dir = proj_dir(1);
+        match try_file_to_str(&dir) {
+            Err(Error::Unreadable(_, _)) => (),
+            x => panic!("expected Unreadable, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn resolver() {
+        let me = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let root = me.parent().unwrap().to_path_buf();
+        let mut r = Resolver::new();
+        r.add("nowhere", root.join("no_such_dir"));
+        r.add("workspace", root.clone());
+        assert_eq!(me.join("src").join("lib.rs"), r.find("proj_self", "src/lib.rs").unwrap());
+
+        match r.find("proj_self", "missing.txt") {
+            Err(Error::NotFound(ref tried)) => {
+                assert_eq!(2, tried.len());
+                assert_eq!("nowhere", tried[0].0);
+                assert_eq!(root.join("proj_self").join("missing.txt"), tried[1].1);
+            },
+            x => panic!("expected NotFound, got {:?}", x),
+        }
+
+        let standard = Resolver::standard(Some(root.join("elsewhere")), env!("CARGO_MANIFEST_DIR"));
+        let msg = standard.find("proj_self", "missing.txt").unwrap_err().to_string();
+        assert!(msg.contains("(from --input)"), "{}", msg);
+        assert!(msg.contains("(from CARGO_MANIFEST_DIR)"), "{}", msg);
+        assert!(msg.contains("(from current directory)"), "{}", msg);
     }
 }
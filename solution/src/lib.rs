@@ -0,0 +1,140 @@
+
+extern crate proj_self;
+
+use std::fmt;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+use proj_self::Error;
+use proj_self::Resolver;
+
+// One day's puzzle. The raw input is parsed once and
+// the parsed form is shared by both parts.
+pub trait Solution {
+    // file holding the puzzle input, relative to the day's crate
+    const INPUT: &'static str = "input.txt";
+
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(input: &str) -> Self::Input;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+}
+
+// answer for a part that has no puzzle
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct NoAnswer;
+
+impl Display for NoAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "-")
+    }
+}
+
+// A Solution with its types erased so that
+// every day can be kept in a single table
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub input: &'static str,
+    pub solve: fn(&str, &[u32]) -> Vec<String>,
+    pub timed: fn(&str, &[u32]) -> Timed,
+}
+
+impl Day {
+    pub fn of<S: Solution>() -> Day {
+        Day { input: S::INPUT, solve: solve_str::<S>, timed: solve_timed::<S> }
+    }
+}
+
+// answer one part of an already parsed input
+pub fn answer<S: Solution>(input: &S::Input, part: u32) -> String {
+    match part {
+        1 => S::part1(input).to_string(),
+        _ => S::part2(input).to_string(),
+    }
+}
+
+// answer each of the requested parts for a raw input
+pub fn solve_str<S: Solution>(input: &str, parts: &[u32]) -> Vec<String> {
+    let parsed = S::parse(input);
+    parts.iter().map(|p| answer::<S>(&parsed, *p)).collect()
+}
+
+// Answers along with the wall clock time spent parsing
+// and on each part
+#[derive(Debug, Clone)]
+pub struct Timed {
+    pub parse: Duration,
+    pub parts: Vec<(u32, String, Duration)>,
+}
+
+// solve_str, timing each step separately
+pub fn solve_timed<S: Solution>(input: &str, parts: &[u32]) -> Timed {
+    let start = Instant::now();
+    let parsed = S::parse(input);
+    let parse = start.elapsed();
+    let parts = parts.iter().map(|p| {
+        let start = Instant::now();
+        let a = answer::<S>(&parsed, *p);
+        (*p, a, start.elapsed())
+    }).collect();
+
+    Timed { parse, parts }
+}
+
+// answer each of the requested parts for an input file
+pub fn solve_file<S: Solution>(file: &PathBuf, parts: &[u32]) -> Result<Vec<String>, Error> {
+    let input = proj_self::try_file_to_str(file)?;
+    Ok(solve_str::<S>(&input, parts))
+}
+
+// find a day's input and answer each of the requested parts
+pub fn solve<S: Solution>(inputs: &Resolver, day: &str, parts: &[u32]) -> Result<Vec<String>, Error> {
+    let file = inputs.find(day, S::INPUT)?;
+    solve_file::<S>(&file, parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use Solution;
+    use NoAnswer;
+    use answer;
+    use Day;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u32>;
+        type Part1 = u32;
+        type Part2 = NoAnswer;
+
+        fn parse(input: &str) -> Vec<u32> {
+            input.split(',').map(|x| x.trim().parse().unwrap()).collect()
+        }
+
+        fn part1(input: &Vec<u32>) -> u32 {
+            input.iter().sum()
+        }
+
+        fn part2(_input: &Vec<u32>) -> NoAnswer {
+            NoAnswer
+        }
+    }
+
+    #[test]
+    fn it_works() {
+        let parsed = Sum::parse("1, 2, 3");
+        assert_eq!("6", answer::<Sum>(&parsed, 1));
+        assert_eq!("-", answer::<Sum>(&parsed, 2));
+
+        let day = Day::of::<Sum>();
+        assert_eq!("input.txt", day.input);
+        assert_eq!(vec!["10"], (day.solve)("4,3,2,1", &[1]));
+        let timed = (day.timed)("4,3,2,1", &[2, 1]);
+        let answers: Vec<(u32, String)> = timed.parts.into_iter().map(|(p, a, _t)| (p, a)).collect();
+        assert_eq!(vec![(2, "-".to_string()), (1, "10".to_string())], answers);
+    }
+}